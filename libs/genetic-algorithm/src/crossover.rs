@@ -42,6 +42,41 @@ impl Default for UniformCrossover {
     }
 }
 
+pub struct SinglePointCrossover;
+
+impl SinglePointCrossover {
+    pub fn new() -> SinglePointCrossover {
+        SinglePointCrossover
+    }
+}
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn rand::RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        // Genes before the point come from 'parent_a', the rest from 'parent_b'
+        let point = rng.gen_range(0..=parent_a.len());
+
+        parent_a
+            .iter()
+            .take(point)
+            .chain(parent_b.iter().skip(point))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for SinglePointCrossover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +103,24 @@ mod tests {
             assert_eq!(diff_b, 51);
         }
     }
+
+    mod single_point {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a: Chromosome = (1..=100).map(|n| n as f32).collect();
+            let parent_b: Chromosome = (1..=100).map(|n| -n as f32).collect();
+            let child = SinglePointCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+
+            // Number of genes taken from 'parent_a' before the crossover point
+            let from_a = child.iter().take_while(|gene| **gene > 0.0).count();
+
+            assert_eq!(from_a, 84);
+            assert!(child.iter().skip(from_a).all(|gene| *gene < 0.0));
+        }
+    }
 }
@@ -31,6 +31,39 @@ impl Default for RouletteWheelSelection {
     }
 }
 
+pub struct TournamentSelection {
+    // Number of individuals competing in each tournament
+    size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> TournamentSelection {
+        assert!(size > 0);
+
+        TournamentSelection { size }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn rand::RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "Received empty population");
+
+        (0..self.size)
+            .map(|_| &population[rng.gen_range(0..population.len())])
+            .reduce(|best, individual| {
+                if individual.fitness() > best.fitness() {
+                    individual
+                } else {
+                    best
+                }
+            })
+            .unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,4 +104,41 @@ mod tests {
             assert_eq!(actual_histogram, expected_histogram);
         }
     }
+
+    mod tournament {
+        use super::*;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+        use std::collections::BTreeMap;
+
+        #[test]
+        fn test() {
+            let method = TournamentSelection::new(2);
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                TestIndividual::new(1.0),
+                TestIndividual::new(2.0),
+                TestIndividual::new(3.0),
+                TestIndividual::new(4.0),
+            ];
+
+            let actual_histogram: BTreeMap<i32, _> = (0..1000)
+                .map(|_| method.select(&mut rng, &population))
+                .fold(Default::default(), |mut histogram, individual| {
+                    *histogram.entry(individual.fitness() as _).or_default() += 1;
+                    histogram
+                });
+
+            let expected_histogram = maplit::btreemap! {
+                // fitness => selection count
+                1 => 60,
+                2 => 192,
+                3 => 321,
+                4 => 427,
+            };
+
+            assert_eq!(actual_histogram, expected_histogram);
+        }
+    }
 }
@@ -0,0 +1,74 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Activation {
+    Relu,
+    Linear,
+    Sigmoid,
+    Tanh,
+}
+
+impl Activation {
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Activation::Relu => x.max(0.0),
+            Activation::Linear => x,
+            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Tanh => x.tanh(),
+        }
+    }
+
+    // Activations of networks that were not given any: the third layer is
+    // linear and every other layer is ReLU
+    pub(crate) fn defaults(layers: usize) -> Vec<Activation> {
+        (0..layers)
+            .map(|i| {
+                if i == 2 {
+                    Activation::Linear
+                } else {
+                    Activation::Relu
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod apply {
+        use super::*;
+        use approx::assert_relative_eq;
+
+        #[test]
+        fn test() {
+            assert_relative_eq!(Activation::Relu.apply(-2.0), 0.0);
+            assert_relative_eq!(Activation::Relu.apply(2.0), 2.0);
+            assert_relative_eq!(Activation::Linear.apply(-2.0), -2.0);
+            assert_relative_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+            assert_relative_eq!(Activation::Sigmoid.apply(2.0), 0.880797);
+            assert_relative_eq!(Activation::Tanh.apply(0.5), 0.46211717);
+        }
+    }
+
+    mod defaults {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(
+                Activation::defaults(4),
+                vec![
+                    Activation::Relu,
+                    Activation::Relu,
+                    Activation::Linear,
+                    Activation::Relu
+                ]
+            );
+        }
+    }
+}
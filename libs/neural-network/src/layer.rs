@@ -18,10 +18,10 @@ impl Layer {
         Layer { neurons }
     }
 
-    pub(crate) fn propagate(&self, inputs: Vec<f32>, activate: Option<bool>) -> Vec<f32> {
+    pub(crate) fn propagate(&self, inputs: Vec<f32>, activation: Activation) -> Vec<f32> {
         self.neurons
             .iter()
-            .map(|neuron| neuron.propagate(&inputs, activate))
+            .map(|neuron| neuron.propagate(&inputs, activation))
             .collect()
     }
 
@@ -86,7 +86,7 @@ mod tests {
                 ],
             };
 
-            let prop = layer.propagate(vec![0.3, 0.6], None);
+            let prop = layer.propagate(vec![0.3, 0.6], Activation::Relu);
             assert_relative_eq!(prop.as_slice(), [0.525, 0.95].as_ref());
         }
     }
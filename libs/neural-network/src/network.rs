@@ -3,6 +3,8 @@ use crate::*;
 #[derive(Debug, Clone)]
 pub struct Network {
     layers: Vec<Layer>,
+    // One for each layer
+    activations: Vec<Activation>,
 }
 
 impl Network {
@@ -12,20 +14,34 @@ impl Network {
         let layers = layers
             .windows(2)
             .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
-            .collect();
+            .collect::<Vec<_>>();
 
-        Network { layers }
+        Network {
+            activations: Activation::defaults(layers.len()),
+            layers,
+        }
     }
 
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        let mut activation_iter = (0..3).map(move |i| match i {
-            0..=1 => true,
-            2 => false,
-            _ => unreachable!(),
-        });
-        self.layers.iter().fold(inputs, |inputs, layer| {
-            layer.propagate(inputs, activation_iter.next())
-        })
+        self.layers
+            .iter()
+            .zip(&self.activations)
+            .fold(inputs, |inputs, (layer, &activation)| {
+                layer.propagate(inputs, activation)
+            })
+    }
+
+    pub fn activations(&self) -> &[Activation] {
+        &self.activations
+    }
+
+    pub fn with_activations(self, activations: &[Activation]) -> Network {
+        assert_eq!(activations.len(), self.layers.len());
+
+        Network {
+            activations: activations.to_vec(),
+            ..self
+        }
     }
 
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
@@ -38,6 +54,14 @@ impl Network {
             .cloned()
     }
 
+    pub fn weight_count(layers: &[LayerTopology]) -> usize {
+        // Every neuron has a bias and a weight for each neuron before it
+        layers
+            .windows(2)
+            .map(|layers| (layers[0].neurons + 1) * layers[1].neurons)
+            .sum()
+    }
+
     pub fn from_weights(
         layers: &[LayerTopology],
         weights: impl IntoIterator<Item = f32>,
@@ -48,13 +72,16 @@ impl Network {
         let layers = layers
             .windows(2)
             .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
-            .collect();
+            .collect::<Vec<_>>();
 
         if weights.next().is_some() {
             panic!("Too many weights!");
         }
 
-        Network { layers }
+        Network {
+            activations: Activation::defaults(layers.len()),
+            layers,
+        }
     }
 }
 
@@ -126,6 +153,7 @@ mod tests {
                         ],
                     },
                 ],
+                activations: vec![Activation::Relu; 2],
             };
 
             let prop = network.propagate(vec![0.3, 0.6]);
@@ -133,6 +161,37 @@ mod tests {
         }
     }
 
+    mod with_activations {
+        use super::*;
+        use approx::assert_relative_eq;
+
+        #[test]
+        fn test() {
+            let layers = &[LayerTopology { neurons: 2 }, LayerTopology { neurons: 1 }];
+            let network = Network::from_weights(layers, vec![-0.5, 0.25, 0.25]);
+            assert_eq!(network.activations(), &[Activation::Relu]);
+            assert_relative_eq!(network.propagate(vec![0.0, 0.0])[0], 0.0);
+
+            let network = network.with_activations(&[Activation::Tanh]);
+            assert_relative_eq!(network.propagate(vec![0.0, 0.0])[0], (-0.5f32).tanh());
+        }
+    }
+
+    mod weight_count {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let layers = &[
+                LayerTopology { neurons: 3 },
+                LayerTopology { neurons: 2 },
+                LayerTopology { neurons: 1 },
+            ];
+
+            assert_eq!(Network::weight_count(layers), 11);
+        }
+    }
+
     mod from_weights {
         use super::*;
         use approx::assert_relative_eq;
@@ -17,19 +17,14 @@ impl Neuron {
         Neuron { weights, bias }
     }
 
-    pub(crate) fn propagate(&self, inputs: &[f32], activate: Option<bool>) -> f32 {
+    pub(crate) fn propagate(&self, inputs: &[f32], activation: Activation) -> f32 {
         let sum = inputs
             .iter()
             .zip(&self.weights)
             .map(|(input, weight)| weight * input)
             .sum::<f32>();
 
-        let activate = activate.unwrap_or(true);
-        if activate {
-            (sum + self.bias).max(0.0)
-        } else {
-            sum + self.bias
-        }
+        activation.apply(sum + self.bias)
     }
 
     pub fn from_weights(output_neurons: usize, weights: &mut dyn Iterator<Item = f32>) -> Neuron {
@@ -74,12 +69,12 @@ mod tests {
             };
 
             // Ensures ReLU activation function is used
-            assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], None), 0.0);
+            assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], Activation::Relu), 0.0);
 
             // Test deactivating the activation function
-            assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], Some(false)), -9.5);
+            assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], Activation::Linear), -9.5);
 
-            assert_relative_eq!(neuron.propagate(&[1.0, 0.5], None), 1.25);
+            assert_relative_eq!(neuron.propagate(&[1.0, 0.5], Activation::Relu), 1.25);
         }
     }
 }
@@ -0,0 +1,6 @@
+//! Snake game engine used to evolve neural network agents.
+//!
+//! The interactive binary in `main.rs` is one consumer of this API; training
+//! and evaluation tooling drive [`snake::game::Game`] directly.
+
+pub mod snake;
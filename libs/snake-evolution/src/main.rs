@@ -1,34 +1,627 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use console::Term;
+use lib_genetic_algorithm::Individual;
+use lib_neural_network::Activation;
+use rand::Rng;
+
+use snake_evolution::snake::agent::Evaluator;
+use snake_evolution::snake::checkpoint::{Checkpoint, Output};
+use snake_evolution::snake::config::GameConfig;
+use snake_evolution::snake::direction::{ActionSpace, Direction};
+use snake_evolution::snake::experiment::Experiment;
+use snake_evolution::snake::fitness::ScoreOnly;
+use snake_evolution::snake::game::{Boundary, Game};
+use snake_evolution::snake::model::Model;
+use snake_evolution::snake::replay::Replay;
+use snake_evolution::snake::train::{
+    Crossover, Fitness, GenerationStats, Selection, TrainConfig, Trainer,
+};
+
+/// Play snake, or evolve neural networks that play it.
+///
+/// Exits with 0 on success, 1 when a command fails (for example a file that
+/// cannot be read or written) and 2 when the arguments are invalid.
+#[derive(Parser)]
+#[command(name = "snake-evolution", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play a game yourself with WASD, any other key quits
+    Play(PlayArgs),
+    /// Evolve a population of agents
+    Train(TrainArgs),
+    /// Animate a saved agent playing a game
+    Watch(WatchArgs),
+    /// Play back a recorded game
+    Replay(ReplayArgs),
+    /// Play a saved agent in many games and print statistics
+    Eval(EvalArgs),
+}
+
+#[derive(Args)]
+struct BoardArgs {
+    /// Number of columns on the board
+    #[arg(long, default_value_t = 10)]
+    width: isize,
+    /// Number of rows on the board
+    #[arg(long, default_value_t = 10)]
+    height: isize,
+    /// What happens at the edges of the board: walls or wrap
+    #[arg(long, default_value = "walls", value_parser = boundary)]
+    boundary: Boundary,
+}
+
+impl BoardArgs {
+    fn config(&self) -> GameConfig {
+        GameConfig {
+            boundary: self.boundary,
+            ..GameConfig::new(self.width, self.height)
+        }
+    }
+}
+
+#[derive(Args)]
+struct PlayArgs {
+    #[command(flatten)]
+    board: BoardArgs,
+    /// Seed for the game, random if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Save the game as a replay to this file
+    #[arg(long)]
+    record: Option<PathBuf>,
+}
+
+// Flags of `train` that an experiment file replaces
+const TRAIN_FLAGS: [&str; 18] = [
+    "width",
+    "height",
+    "boundary",
+    "seed",
+    "population",
+    "generations",
+    "hidden",
+    "activation",
+    "output_activation",
+    "games",
+    "max_steps",
+    "selection",
+    "crossover",
+    "mutation_rate",
+    "mutation_strength",
+    "fitness",
+    "actions",
+    "out",
+];
+
+#[derive(Args)]
+struct TrainArgs {
+    /// Experiment file to read the run from instead of flags. The resolved
+    /// config, statistics and best agent are written to its output directory
+    #[arg(long, conflicts_with_all = TRAIN_FLAGS)]
+    config: Option<PathBuf>,
+    /// Checkpoint to carry on a run from. The statistics and best agent are
+    /// written wherever the run first wrote them
+    #[arg(long, conflicts_with_all = TRAIN_FLAGS, conflicts_with = "config")]
+    resume: Option<PathBuf>,
+    /// File to save checkpoints to, by default checkpoint.json in the output
+    /// directory of an experiment, or the checkpoint resumed from
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+    /// Generations between checkpoints
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    checkpoint_every: u64,
+    #[command(flatten)]
+    board: BoardArgs,
+    /// Seed for everything random in the run
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Number of agents in every generation
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+    population: u64,
+    /// Number of generations to evolve
+    #[arg(long, default_value_t = 50)]
+    generations: usize,
+    /// Sizes of the hidden layers, separated by commas
+    #[arg(long, default_value = "16", value_delimiter = ',')]
+    hidden: Vec<usize>,
+    /// Activation of the hidden layers: relu, linear, sigmoid or tanh
+    #[arg(long, default_value = "relu", value_parser = activation)]
+    activation: Activation,
+    /// Activation of the output layer: relu, linear, sigmoid or tanh
+    #[arg(long, default_value = "linear", value_parser = activation)]
+    output_activation: Activation,
+    /// Games every agent plays per generation
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..))]
+    games: u64,
+    /// Moves after which a game is stopped
+    #[arg(long, default_value_t = 1000)]
+    max_steps: usize,
+    /// How parents are picked: roulette or tournament:<size>
+    #[arg(long, default_value = "roulette", value_parser = selection)]
+    selection: Selection,
+    /// How parents' genes are combined: uniform or single-point
+    #[arg(long, default_value = "uniform", value_parser = crossover)]
+    crossover: Crossover,
+    /// Probability of mutating each gene of a child
+    #[arg(long, default_value_t = 0.05)]
+    mutation_rate: f32,
+    /// Largest change a mutation makes to a gene
+    #[arg(long, default_value_t = 0.3)]
+    mutation_strength: f32,
+    /// How games are scored: score, survival:<reward per move> or classic
+    #[arg(long, default_value = "classic", value_parser = fitness)]
+    fitness: Fitness,
+    /// Which moves the agents choose from: absolute or relative
+    #[arg(long, default_value = "relative", value_parser = actions)]
+    actions: ActionSpace,
+    /// Save the best agent as a model to this file
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
 
-use snake::direction::Direction;
-use snake::game::Game;
+impl TrainArgs {
+    fn train_config(&self) -> TrainConfig {
+        let default = TrainConfig::default();
+
+        TrainConfig {
+            population: self.population as usize,
+            generations: self.generations,
+            hidden: self.hidden.clone(),
+            activation: self.activation,
+            output_activation: self.output_activation,
+            seed: self.seed,
+            games: self.games as usize,
+            max_steps: self.max_steps,
+            selection: self.selection,
+            crossover: self.crossover,
+            mutation_rate: self.mutation_rate,
+            mutation_strength: self.mutation_strength,
+            fitness: self.fitness,
+            game: GameConfig {
+                width: self.board.width.max(3),
+                height: self.board.height.max(3),
+                boundary: self.board.boundary,
+                actions: self.actions,
+                ..default.game
+            },
+        }
+    }
+}
+
+#[derive(Args)]
+struct WatchArgs {
+    /// Model saved by `train --out`
+    model: PathBuf,
+    /// Seed for the game
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Moves after which the game is stopped
+    #[arg(long, default_value_t = 1000)]
+    max_steps: usize,
+    /// Milliseconds between moves
+    #[arg(long, default_value_t = 100)]
+    delay: u64,
+    /// Save the game as a replay to this file
+    #[arg(long)]
+    record: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ReplayArgs {
+    /// Replay saved by `play --record` or `watch --record`
+    file: PathBuf,
+    /// Milliseconds between moves
+    #[arg(long, default_value_t = 100)]
+    delay: u64,
+}
+
+#[derive(Args)]
+struct EvalArgs {
+    /// Model saved by `train --out`
+    model: PathBuf,
+    /// Seed of the first game, the others follow on from it
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Number of games to play
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u64).range(1..))]
+    games: u64,
+    /// Moves after which a game is stopped
+    #[arg(long, default_value_t = 1000)]
+    max_steps: usize,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Play(args) => play(&args),
+        Command::Train(args) => train(&args),
+        Command::Watch(args) => watch(&args),
+        Command::Replay(args) => replay(&args),
+        Command::Eval(args) => eval(&args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-mod snake;
+type CommandResult = Result<(), Box<dyn std::error::Error>>;
 
-fn main() {
+fn play(args: &PlayArgs) -> CommandResult {
     let stdout = Term::buffered_stdout();
-    let mut game = Game::new(10);
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut replay = Replay::new(args.board.config(), seed);
+    let mut game = replay.start();
 
     'game_loop: loop {
-        print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+        clear_screen();
         println!("{}", game.display());
 
-        let direction = if let Ok(character) = stdout.read_char() {
-            match character {
-                'w' => Direction::Up,
-                'a' => Direction::Left,
-                's' => Direction::Down,
-                'd' => Direction::Right,
-                _ => break 'game_loop,
+        let direction = match stdout.read_char()? {
+            'w' => Direction::Up,
+            'a' => Direction::Left,
+            's' => Direction::Down,
+            'd' => Direction::Right,
+            _ => break 'game_loop,
+        };
+
+        let outcome = game.move_snake(direction);
+        replay.moves.push(direction);
+
+        if let Some(death_cause) = outcome.death_cause {
+            clear_screen();
+            println!("{}", game.display());
+            println!("Game over! The snake {}.", death_cause);
+            break 'game_loop;
+        }
+    }
+
+    if let Some(path) = &args.record {
+        replay.save(path)?;
+    }
+
+    Ok(())
+}
+
+fn train(args: &TrainArgs) -> CommandResult {
+    // Where the results go, and where checkpoints go
+    let (mut trainer, output, checkpoint) = if let Some(path) = &args.resume {
+        let resumed = Checkpoint::load(path)?;
+        let output = resumed.output.clone();
+        let checkpoint = args.checkpoint.clone().unwrap_or_else(|| path.clone());
+        let trainer = Trainer::resume(resumed);
+        println!("resuming at generation {}", trainer.generation());
+
+        (trainer, output, Some(checkpoint))
+    } else if let Some(path) = &args.config {
+        let experiment = Experiment::load(path)?;
+        std::fs::create_dir_all(&experiment.output)?;
+        std::fs::write(experiment.output.join("config.toml"), experiment.to_toml())?;
+        let checkpoint = args
+            .checkpoint
+            .clone()
+            .unwrap_or_else(|| experiment.output.join("checkpoint.json"));
+
+        (
+            Trainer::new(&experiment.train),
+            Some(Output::Directory(experiment.output)),
+            Some(checkpoint),
+        )
+    } else {
+        let config = args.train_config();
+        if let Err(err) = config.validate() {
+            Cli::command().error(ErrorKind::ValueValidation, err).exit();
+        }
+
+        (
+            Trainer::new(&config),
+            args.out.clone().map(Output::Model),
+            args.checkpoint.clone(),
+        )
+    };
+
+    // A resumed run rewrites the statistics of the generations before it
+    let mut stats = match &output {
+        Some(Output::Directory(dir)) => {
+            let mut file = File::create(dir.join("stats.csv"))?;
+            writeln!(file, "generation,best,average,worst")?;
+            for generation in trainer.history() {
+                write_stats(&mut file, generation)?;
             }
-        } else {
-            panic!("Failed to read input")
+            Some(file)
+        }
+        _ => None,
+    };
+
+    let generations = trainer.config().generations;
+    while trainer.generation() < generations {
+        let generation = trainer.evolve();
+        println!(
+            "generation {:>4}  best {:>10.2}  average {:>10.2}  worst {:>10.2}",
+            generation.generation, generation.best, generation.average, generation.worst
+        );
+
+        if let Some(file) = &mut stats {
+            write_stats(file, &generation)?;
+        }
+        if let Some(path) = &checkpoint {
+            let done = trainer.generation();
+            if (done as u64).is_multiple_of(args.checkpoint_every) || done == generations {
+                let checkpoint = Checkpoint {
+                    output: output.clone(),
+                    ..trainer.checkpoint()
+                };
+                checkpoint.save(path)?;
+            }
+        }
+    }
+
+    if let Some(best) = trainer.best() {
+        println!("best fitness {:.2}", best.fitness());
+
+        let path = match &output {
+            Some(Output::Directory(dir)) => Some(dir.join("best.json")),
+            Some(Output::Model(path)) => Some(path.clone()),
+            None => None,
         };
+        if let Some(path) = path {
+            Model::new(trainer.evaluator(), best).save(&path)?;
+            println!("saved best agent to {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn write_stats(file: &mut File, generation: &GenerationStats) -> std::io::Result<()> {
+    writeln!(
+        file,
+        "{},{},{},{}",
+        generation.generation, generation.best, generation.average, generation.worst
+    )
+}
+
+fn watch(args: &WatchArgs) -> CommandResult {
+    let model = Model::load(&args.model)?;
+    let network = model.network();
+    let evaluator = evaluator(&model, args.max_steps);
+    let mut replay = Replay::new(model.config.clone(), args.seed);
+    let mut game = replay.start();
 
+    while !game.finished() && replay.moves.len() < args.max_steps {
+        show(&game, args.delay);
+
+        let direction = evaluator.act(&network, &game);
         game.move_snake(direction);
+        replay.moves.push(direction);
+    }
+    show(&game, 0);
+    report(&game);
 
-        if game.finished() {
-            break 'game_loop;
+    if let Some(path) = &args.record {
+        replay.save(path)?;
+    }
+
+    Ok(())
+}
+
+fn replay(args: &ReplayArgs) -> CommandResult {
+    let replay = Replay::load(&args.file)?;
+    let mut game = replay.start();
+
+    for &direction in &replay.moves {
+        show(&game, args.delay);
+        game.move_snake(direction);
+    }
+    show(&game, 0);
+    report(&game);
+
+    Ok(())
+}
+
+fn eval(args: &EvalArgs) -> CommandResult {
+    let model = Model::load(&args.model)?;
+    let network = model.network();
+    let evaluator = evaluator(&model, args.max_steps);
+
+    let mut scores = Vec::new();
+    let mut steps = Vec::new();
+    let mut endings: BTreeMap<String, usize> = BTreeMap::new();
+
+    for seed in (args.seed..).take(args.games as usize) {
+        let (game, trajectory) = evaluator.play(&network, seed);
+        scores.push(game.score() as f32);
+        steps.push(trajectory.steps() as f32);
+
+        let ending = match game.death_cause() {
+            Some(cause) => cause.to_string(),
+            None => "ran out of moves".to_string(),
+        };
+        *endings.entry(ending).or_default() += 1;
+    }
+
+    println!("games  {}", args.games);
+    println!("score  {}", summary(&scores));
+    println!("moves  {}", summary(&steps));
+    for (ending, count) in endings {
+        println!("{:>5}  {}", count, ending);
+    }
+
+    Ok(())
+}
+
+fn evaluator(model: &Model, max_steps: usize) -> Evaluator {
+    Evaluator {
+        config: model.config.clone(),
+        topology: model.topology(),
+        activations: model.activations.clone(),
+        fitness: Box::new(ScoreOnly),
+        max_steps,
+    }
+}
+
+fn show(game: &Game, delay: u64) {
+    clear_screen();
+    println!("{}", game.display());
+    thread::sleep(Duration::from_millis(delay));
+}
+
+fn report(game: &Game) {
+    match game.death_cause() {
+        Some(cause) => println!("The snake {}.", cause),
+        None => println!("The game was stopped."),
+    }
+}
+
+fn clear_screen() {
+    print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+}
+
+// Mean, lowest and highest of `values`
+fn summary(values: &[f32]) -> String {
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    let min = values.iter().cloned().fold(f32::MAX, f32::min);
+    let max = values.iter().cloned().fold(f32::MIN, f32::max);
+
+    format!("mean {:.2}  min {}  max {}", mean, min, max)
+}
+
+fn boundary(value: &str) -> Result<Boundary, String> {
+    match value {
+        "walls" => Ok(Boundary::Walls),
+        "wrap" => Ok(Boundary::Wrap),
+        _ => Err("expected walls or wrap".to_string()),
+    }
+}
+
+fn actions(value: &str) -> Result<ActionSpace, String> {
+    match value {
+        "absolute" => Ok(ActionSpace::Absolute),
+        "relative" => Ok(ActionSpace::Relative),
+        _ => Err("expected absolute or relative".to_string()),
+    }
+}
+
+fn activation(value: &str) -> Result<Activation, String> {
+    match value {
+        "relu" => Ok(Activation::Relu),
+        "linear" => Ok(Activation::Linear),
+        "sigmoid" => Ok(Activation::Sigmoid),
+        "tanh" => Ok(Activation::Tanh),
+        _ => Err("expected relu, linear, sigmoid or tanh".to_string()),
+    }
+}
+
+fn selection(value: &str) -> Result<Selection, String> {
+    match value.split_once(':') {
+        None if value == "roulette" => Ok(Selection::RouletteWheel),
+        Some(("tournament", size)) => match size.parse() {
+            Ok(size) if size > 0 => Ok(Selection::Tournament(size)),
+            _ => Err("tournament size must be a positive number".to_string()),
+        },
+        _ => Err("expected roulette or tournament:<size>".to_string()),
+    }
+}
+
+fn crossover(value: &str) -> Result<Crossover, String> {
+    match value {
+        "uniform" => Ok(Crossover::Uniform),
+        "single-point" => Ok(Crossover::SinglePoint),
+        _ => Err("expected uniform or single-point".to_string()),
+    }
+}
+
+fn fitness(value: &str) -> Result<Fitness, String> {
+    match value.split_once(':') {
+        None if value == "score" => Ok(Fitness::Score),
+        None if value == "classic" => Ok(Fitness::Classic),
+        Some(("survival", survival)) => survival
+            .parse()
+            .map(Fitness::ScoreAndSurvival)
+            .map_err(|_| "survival reward must be a number".to_string()),
+        _ => Err("expected score, survival:<reward per move> or classic".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str]) -> CommandResult {
+        let args = std::iter::once("snake-evolution").chain(args.iter().cloned());
+
+        match Cli::try_parse_from(args).unwrap().command {
+            Command::Train(args) => train(&args),
+            _ => unreachable!(),
+        }
+    }
+
+    mod train {
+        use super::*;
+
+        #[test]
+        fn test_resume_output() {
+            let dir = std::env::temp_dir().join(format!("snake-cli-{}-resume", std::process::id()));
+            let results = dir.join("results");
+            std::fs::create_dir_all(&results).unwrap();
+            let checkpoint = dir.join("checkpoint.json");
+            let model = results.join("model.json");
+            let path = |path: &std::path::Path| path.to_str().unwrap().to_string();
+
+            run(&[
+                "train",
+                "--population",
+                "4",
+                "--generations",
+                "2",
+                "--hidden",
+                "2",
+                "--max-steps",
+                "20",
+                "--checkpoint",
+                &path(&checkpoint),
+                "--out",
+                &path(&model),
+            ])
+            .unwrap();
+            std::fs::remove_file(&model).unwrap();
+
+            // Carry the run on for another generation
+            let mut saved = Checkpoint::load(&checkpoint).unwrap();
+            saved.config.generations = 3;
+            saved.save(&checkpoint).unwrap();
+            run(&["train", "--resume", &path(&checkpoint)]).unwrap();
+
+            // The best agent goes where the run first put it, and nothing
+            // is written next to the checkpoint
+            assert!(Model::load(&model).is_ok());
+            let mut files: Vec<_> = std::fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect();
+            files.sort();
+            assert_eq!(files, ["checkpoint.json", "results"]);
+
+            std::fs::remove_dir_all(&dir).unwrap();
         }
     }
 }
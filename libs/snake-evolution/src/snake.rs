@@ -1,2 +1,21 @@
-pub(crate) mod direction;
-pub(crate) mod game;
+pub mod agent;
+pub mod arena;
+pub mod batch;
+mod board;
+pub mod checkpoint;
+pub mod config;
+pub mod direction;
+pub mod env;
+pub mod experiment;
+pub mod fitness;
+#[cfg(test)]
+mod fixtures;
+pub mod food;
+pub mod game;
+pub mod hunger;
+pub mod level;
+pub mod model;
+pub mod observation;
+pub mod replay;
+pub mod save;
+pub mod train;
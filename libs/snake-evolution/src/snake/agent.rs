@@ -0,0 +1,250 @@
+use lib_genetic_algorithm::{Chromosome, Individual};
+use lib_neural_network::{Activation, LayerTopology, Network};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::snake::config::GameConfig;
+use crate::snake::direction::Direction;
+use crate::snake::fitness::{FitnessFunction, Trajectory};
+use crate::snake::game::Game;
+
+/// Lowest fitness an agent is given, so roulette wheel selection always has
+/// something to pick from even when every agent did badly.
+pub const MIN_FITNESS: f32 = 0.001;
+
+/// A snake controlled by a neural network whose weights are its genes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SnakeAgent {
+    #[serde(with = "genes")]
+    chromosome: Chromosome,
+    fitness: f32,
+}
+
+// Chromosomes are saved as plain lists of genes
+mod genes {
+    use lib_genetic_algorithm::Chromosome;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        chromosome: &Chromosome,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(chromosome.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Chromosome, D::Error> {
+        Vec::<f32>::deserialize(deserializer).map(|genes| genes.into_iter().collect())
+    }
+}
+
+impl SnakeAgent {
+    /// Agent with random weights for a network shaped like `topology`.
+    pub fn random(rng: &mut dyn rand::RngCore, topology: &[LayerTopology]) -> SnakeAgent {
+        let network = Network::random(rng, topology);
+
+        SnakeAgent::create(network.weights().collect())
+    }
+
+    /// Network shaped like `topology`, with one of `activations` for each
+    /// layer after the first, that controls the snake.
+    pub fn network(&self, topology: &[LayerTopology], activations: &[Activation]) -> Network {
+        Network::from_weights(topology, self.chromosome.iter().cloned())
+            .with_activations(activations)
+    }
+}
+
+impl Individual for SnakeAgent {
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    fn create(chromosome: Chromosome) -> SnakeAgent {
+        SnakeAgent {
+            chromosome,
+            fitness: MIN_FITNESS,
+        }
+    }
+}
+
+/// Plays agents in seeded games and scores them.
+pub struct Evaluator {
+    /// Rules of every game played.
+    pub config: GameConfig,
+    /// Shape of the agents' networks. The first layer must match the size of
+    /// the config's observations and the last its number of actions.
+    pub topology: Vec<LayerTopology>,
+    /// Activation of every layer of the agents' networks after the first.
+    pub activations: Vec<Activation>,
+    /// How a finished game is scored.
+    pub fitness: Box<dyn FitnessFunction>,
+    /// Moves after which a game is stopped, even if it has not finished.
+    pub max_steps: usize,
+}
+
+impl Evaluator {
+    /// Plays a game started from `seed`, moving the snake in the action
+    /// `network` rates highest after observing the game, until the game ends
+    /// or `max_steps` moves have been made.
+    pub fn play(&self, network: &Network, seed: u64) -> (Game, Trajectory) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut game = Game::from_config(&mut rng, self.config.clone());
+        let mut trajectory = Trajectory::new(&game);
+
+        while !game.finished() && trajectory.steps() < self.max_steps {
+            let outcome = game.move_snake(self.act(network, &game));
+            trajectory.record(&game, outcome);
+        }
+
+        (game, trajectory)
+    }
+
+    /// Direction of the action `network` rates highest after observing
+    /// `game`.
+    pub fn act(&self, network: &Network, game: &Game) -> Direction {
+        let outputs = network.propagate(game.observe());
+        assert_eq!(outputs.len(), self.config.actions.size());
+
+        // Ties go to the first action
+        let action = (0..outputs.len())
+            .reduce(|best, i| if outputs[i] > outputs[best] { i } else { best })
+            .unwrap();
+
+        self.config.actions.direction(action, game.heading())
+    }
+
+    /// Sets the fitness of `agent` to its average over a game from each of
+    /// `seeds`, but never below [`MIN_FITNESS`].
+    pub fn evaluate(&self, agent: &mut SnakeAgent, seeds: &[u64]) {
+        assert!(!seeds.is_empty());
+
+        let network = agent.network(&self.topology, &self.activations);
+        let total: f32 = seeds
+            .iter()
+            .map(|&seed| {
+                let (game, trajectory) = self.play(&network, seed);
+                self.fitness.fitness(&game, &trajectory)
+            })
+            .sum();
+
+        agent.fitness = (total / seeds.len() as f32).max(MIN_FITNESS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::direction::ActionSpace;
+    use crate::snake::fitness::{ScoreAndSurvival, ScoreOnly};
+    use crate::snake::observation::{Encoding, RayVision};
+
+    fn topology() -> Vec<LayerTopology> {
+        vec![
+            LayerTopology {
+                neurons: RayVision::SIZE,
+            },
+            LayerTopology { neurons: 8 },
+            LayerTopology { neurons: 3 },
+        ]
+    }
+
+    fn activations() -> Vec<Activation> {
+        vec![Activation::Relu, Activation::Linear]
+    }
+
+    fn evaluator() -> Evaluator {
+        Evaluator {
+            config: GameConfig {
+                observation: Encoding::Rays(RayVision::default()),
+                actions: ActionSpace::Relative,
+                ..GameConfig::new(10, 10)
+            },
+            topology: topology(),
+            activations: activations(),
+            fitness: Box::new(ScoreAndSurvival { survival: 0.01 }),
+            max_steps: 200,
+        }
+    }
+
+    mod snake_agent {
+        use super::*;
+
+        #[test]
+        fn test_random() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let agent = SnakeAgent::random(&mut rng, &topology());
+
+            assert_eq!(agent.chromosome().len(), (32 + 1) * 8 + (8 + 1) * 3);
+            assert_eq!(agent.fitness(), MIN_FITNESS);
+        }
+
+        #[test]
+        fn test_network() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random(&mut rng, &topology());
+            let agent = SnakeAgent::create(network.weights().collect());
+
+            let controller = agent.network(&topology(), &activations());
+            assert!(controller.weights().eq(network.weights()));
+            assert_eq!(controller.activations(), activations().as_slice());
+        }
+    }
+
+    mod evaluator {
+        use super::*;
+
+        #[test]
+        fn test_play() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = evaluator();
+            let network =
+                SnakeAgent::random(&mut rng, &topology()).network(&topology(), &activations());
+
+            let (game, trajectory) = evaluator.play(&network, 1);
+            assert!(game.finished() || trajectory.steps() == 200);
+            assert!(trajectory.steps() <= 200);
+
+            // Games from the same seed play out the same way
+            let (again, _) = evaluator.play(&network, 1);
+            assert_eq!(again.snake(), game.snake());
+        }
+
+        #[test]
+        fn test_evaluate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = evaluator();
+            let mut agent = SnakeAgent::random(&mut rng, &topology());
+
+            evaluator.evaluate(&mut agent, &[1, 2, 3]);
+
+            let network = agent.network(&topology(), &activations());
+            let expected: f32 = [1, 2, 3]
+                .iter()
+                .map(|&seed| {
+                    let (game, trajectory) = evaluator.play(&network, seed);
+                    evaluator.fitness.fitness(&game, &trajectory)
+                })
+                .sum::<f32>()
+                / 3.0;
+            assert_eq!(agent.fitness(), expected.max(MIN_FITNESS));
+        }
+
+        #[test]
+        fn test_fitness_floor() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let evaluator = Evaluator {
+                fitness: Box::new(ScoreOnly),
+                max_steps: 0,
+                ..evaluator()
+            };
+            let mut agent = SnakeAgent::random(&mut rng, &topology());
+
+            evaluator.evaluate(&mut agent, &[0]);
+            assert_eq!(agent.fitness(), MIN_FITNESS);
+        }
+    }
+}
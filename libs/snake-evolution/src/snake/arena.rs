@@ -0,0 +1,836 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::snake::board::{Board, Body};
+use crate::snake::config::GameConfig;
+use crate::snake::direction::Direction;
+use crate::snake::food::{Food, FoodKind, FoodSpawn};
+use crate::snake::game::{DeathCause, StepOutcome, Tile};
+
+/// Record of a snake being knocked out of an [`Arena`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elimination {
+    /// Index of the eliminated snake.
+    pub snake: usize,
+    /// Tick the snake was eliminated on, counting the first tick as 1.
+    pub tick: usize,
+    /// Why the snake was eliminated.
+    pub cause: DeathCause,
+}
+
+/// Rules in a [`GameConfig`] that an [`Arena`] cannot play by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArenaError {
+    /// Food placed far from the head, when there are several heads.
+    FarFromHead,
+    /// Food other than normal food, which is given a spawn weight.
+    FoodKind(FoodKind),
+}
+
+struct Contestant {
+    body: Body,
+    heading: Option<Direction>,
+    score: usize,
+    death_cause: Option<DeathCause>,
+}
+
+impl Contestant {
+    fn alive(&self) -> bool {
+        self.death_cause.is_none()
+    }
+}
+
+/// Several snakes sharing one board and moving at the same time.
+///
+/// Every tick all live snakes move at once and collisions are resolved
+/// against the board as it was before the tick:
+///
+/// - Heads arriving on the same cell eliminate all of those snakes, even when
+///   the cell holds food, so contested food is never eaten.
+/// - A head moving into any body cell, including a tail that would have moved
+///   away, eliminates that snake. Two snakes swapping cells run into each
+///   other's bodies and are both eliminated.
+/// - Eliminated snakes are taken off the board straight away.
+///
+/// The arena follows the board, boundary, start length, growth, reversal,
+/// hunger, food count and food spawn rules of its [`GameConfig`], placing
+/// only normal food. The arena is over once at most one snake is left, or
+/// once the only snake is eliminated when it started with one.
+pub struct Arena {
+    config: GameConfig,
+    board: Board,
+    snakes: Vec<Contestant>,
+    food: Vec<Food>,
+    tick: usize,
+    eliminations: Vec<Elimination>,
+}
+
+impl Arena {
+    /// Creates a new arena following the rules in `config`, with one snake
+    /// for each of the `spawns`, given as the cell its head starts on and the
+    /// direction it starts facing. Bodies are laid out behind the heads as
+    /// far as the board allows and grown over the first moves otherwise.
+    ///
+    /// The spawn cell and heading in `config` are replaced by `spawns`. Food
+    /// spawns and kinds the arena cannot play by are refused. An arena with
+    /// no free cell left for food starts out over, with every snake
+    /// eliminated on tick 0.
+    pub fn new(
+        rng: &mut dyn rand::RngCore,
+        config: GameConfig,
+        spawns: &[(isize, Direction)],
+    ) -> Result<Arena, ArenaError> {
+        let area = config.width * config.height;
+        let heads: HashSet<isize> = spawns.iter().map(|(cell, _)| *cell).collect();
+
+        if config.food_spawn == FoodSpawn::FarFromHead {
+            return Err(ArenaError::FarFromHead);
+        }
+        if let Some((kind, _)) = config
+            .food_rules
+            .weights()
+            .into_iter()
+            .find(|&(kind, weight)| kind != FoodKind::Normal && weight > 0.0)
+        {
+            return Err(ArenaError::FoodKind(kind));
+        }
+
+        assert!(config.width > 0 && config.height > 0);
+        assert!(config.start_length > 0);
+        assert!(config.food_count > 0);
+        assert!(config.food_rules.normal_weight > 0.0);
+        assert!(!spawns.is_empty());
+        assert_eq!(heads.len(), spawns.len());
+        assert!(heads
+            .iter()
+            .all(|cell| (0..area).contains(cell) && !config.walls.contains(cell)));
+
+        let mut board = Board::new(rng, &config);
+        for &(cell, _) in spawns {
+            board.set_tile(cell, Tile::Snake);
+        }
+
+        // Bodies are laid out in order, each around the heads and the bodies
+        // before it
+        let snakes = spawns
+            .iter()
+            .map(|&(cell, heading)| Contestant {
+                body: board.spawn(&config, cell, Some(heading)),
+                heading: Some(heading),
+                score: 0,
+                death_cause: None,
+            })
+            .collect();
+
+        let mut arena = Arena {
+            board,
+            snakes,
+            food: Vec::with_capacity(config.food_count),
+            tick: 0,
+            eliminations: Vec::new(),
+            config,
+        };
+        // Snakes that leave no room for food have filled the board already
+        if !arena.board.top_up(&arena.config, &mut arena.food, None) {
+            for i in 0..arena.snakes.len() {
+                arena.eliminate(i, DeathCause::BoardFull);
+            }
+        }
+
+        Ok(arena)
+    }
+
+    /// Moves every live snake one cell in its entry of `directions` and
+    /// advances the arena by one tick. Entries for eliminated snakes are
+    /// ignored.
+    ///
+    /// Returns what happened to each snake on this tick. A snake's outcome
+    /// is `finished` once it has been eliminated or the arena is over.
+    /// Ticks made after the arena is over are ignored.
+    pub fn move_snakes(&mut self, directions: &[Direction]) -> Vec<StepOutcome> {
+        assert_eq!(directions.len(), self.snakes.len());
+
+        if self.finished() {
+            return vec![
+                StepOutcome {
+                    finished: true,
+                    ..Default::default()
+                };
+                self.snakes.len()
+            ];
+        }
+
+        self.tick += 1;
+        let mut outcomes = vec![StepOutcome::default(); self.snakes.len()];
+        let mut heads = vec![None; self.snakes.len()];
+        let mut causes = vec![None; self.snakes.len()];
+
+        // Work out where every live snake is heading
+        for (i, snake) in self.snakes.iter_mut().enumerate() {
+            if !snake.alive() {
+                continue;
+            }
+
+            let length = snake.body.cells.len();
+            let direction = match self.config.steer(snake.heading, directions[i], length) {
+                Some(direction) => direction,
+                None => continue,
+            };
+
+            match self.config.advance(snake.body.head(), direction) {
+                Ok(cell) => heads[i] = Some(cell),
+                Err(cause) => causes[i] = Some(cause),
+            }
+            snake.heading = Some(direction);
+        }
+
+        // Resolve collisions against the board as it was before the tick
+        let mut arrivals: HashMap<isize, usize> = HashMap::new();
+        for head in heads.iter().flatten() {
+            *arrivals.entry(*head).or_default() += 1;
+        }
+
+        for (i, head) in heads.iter().enumerate() {
+            if let Some(head) = head {
+                causes[i] = match self.board.tile(*head) {
+                    Tile::Wall => Some(DeathCause::Obstacle),
+                    _ if arrivals[head] > 1 => Some(DeathCause::HeadToHead),
+                    Tile::Snake if self.snakes[i].body.cells.contains(head) => {
+                        Some(DeathCause::SelfCollision)
+                    }
+                    Tile::Snake => Some(DeathCause::SnakeCollision),
+                    Tile::Food | Tile::Empty => None,
+                };
+            }
+        }
+
+        for (i, cause) in causes.iter().enumerate() {
+            if let Some(cause) = *cause {
+                outcomes[i] = self.eliminate(i, cause);
+            }
+        }
+
+        // Move the survivors and let them eat
+        for (i, head) in heads.iter().enumerate() {
+            let snake = &mut self.snakes[i];
+            let head = match head {
+                Some(head) if snake.alive() => *head,
+                _ => continue,
+            };
+            if self.board.tile(head) == Tile::Food {
+                let food = self.food.iter().position(|food| food.cell == head).unwrap();
+                self.food.swap_remove(food);
+                snake.score += 1;
+                snake.body.feed(self.config.growth);
+
+                outcomes[i] = StepOutcome {
+                    ate_food: true,
+                    eaten: Some(FoodKind::Normal),
+                    score_delta: 1,
+                    ..Default::default()
+                };
+            }
+            self.board.move_body(&mut snake.body, head);
+        }
+
+        // A board with no room left for food knocks out every survivor, and
+        // hunger each one that did not eat
+        let food_left = self.board.top_up(&self.config, &mut self.food, None);
+        for (i, outcome) in outcomes.iter_mut().enumerate() {
+            if !self.snakes[i].alive() {
+                continue;
+            }
+
+            if !food_left {
+                *outcome = self.eliminate(i, DeathCause::BoardFull);
+            } else if !outcome.ate_food && self.snakes[i].body.go_hungry() {
+                *outcome = self.eliminate(i, DeathCause::Starvation);
+            }
+        }
+
+        let finished = self.finished();
+        for (outcome, snake) in outcomes.iter_mut().zip(&self.snakes) {
+            outcome.finished = finished || !snake.alive();
+        }
+
+        outcomes
+    }
+
+    // Takes the snake off the board, though it keeps its body
+    fn eliminate(&mut self, snake: usize, cause: DeathCause) -> StepOutcome {
+        self.snakes[snake].death_cause = Some(cause);
+        for &cell in &self.snakes[snake].body.cells {
+            self.board.set_tile(cell, Tile::Empty);
+        }
+        self.eliminations.push(Elimination {
+            snake,
+            tick: self.tick,
+            cause,
+        });
+
+        StepOutcome {
+            finished: true,
+            death_cause: Some(cause),
+            ..Default::default()
+        }
+    }
+
+    /// Renders the board, live snakes, food and scores as text. Snakes are
+    /// labelled by letter, with the head in upper case.
+    pub fn display(&self) -> String {
+        let mut labels = HashMap::new();
+        for (i, snake) in self.snakes.iter().enumerate().filter(|(_, s)| s.alive()) {
+            let label = char::from(b'a' + (i % 26) as u8);
+            labels.extend(snake.body.cells.iter().map(|&cell| (cell, label)));
+            labels.insert(snake.body.head(), label.to_ascii_uppercase());
+        }
+
+        let mut out = self.board.display(|i| match labels.get(&i) {
+            Some(&label) => label,
+            None => FoodKind::Normal.glyph(),
+        });
+
+        let scores: Vec<String> = (0..self.snakes.len())
+            .map(|i| {
+                let label = char::from(b'A' + (i % 26) as u8);
+                format!("{}: {}", label, self.snakes[i].score)
+            })
+            .collect();
+        out += &format!("Scores: {}", scores.join(", "));
+
+        out
+    }
+
+    /// Number of snakes the arena started with.
+    pub fn len(&self) -> usize {
+        self.snakes.len()
+    }
+
+    /// Whether the arena has no snakes, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.snakes.is_empty()
+    }
+
+    /// Cells occupied by snake `i`, ordered from tail to head. Eliminated
+    /// snakes keep the body they had when they were eliminated.
+    pub fn snake(&self, i: usize) -> &VecDeque<isize> {
+        &self.snakes[i].body.cells
+    }
+
+    /// Cell occupied by the head of snake `i`.
+    pub fn head(&self, i: usize) -> isize {
+        self.snakes[i].body.head()
+    }
+
+    /// Direction snake `i` last moved in, or started facing if it has not
+    /// moved yet.
+    pub fn heading(&self, i: usize) -> Option<Direction> {
+        self.snakes[i].heading
+    }
+
+    /// Whether snake `i` is still in play.
+    pub fn alive(&self, i: usize) -> bool {
+        self.snakes[i].alive()
+    }
+
+    /// Number of food items eaten by snake `i`.
+    pub fn score(&self, i: usize) -> usize {
+        self.snakes[i].score
+    }
+
+    /// Number of food items eaten by each snake.
+    pub fn scores(&self) -> Vec<usize> {
+        self.snakes.iter().map(|snake| snake.score).collect()
+    }
+
+    /// Why snake `i` was eliminated, or `None` while it is still in play.
+    pub fn death_cause(&self, i: usize) -> Option<DeathCause> {
+        self.snakes[i].death_cause
+    }
+
+    /// Moves snake `i` can still make without eating, or `None` if the
+    /// arena has no hunger limit.
+    pub fn moves_left(&self, i: usize) -> Option<usize> {
+        self.snakes[i].body.moves_left()
+    }
+
+    /// Snakes knocked out so far, in the order they were eliminated. Snakes
+    /// eliminated on the same tick are ordered by index.
+    pub fn eliminations(&self) -> &[Elimination] {
+        &self.eliminations
+    }
+
+    /// Last snake standing, once every other snake has been eliminated.
+    pub fn winner(&self) -> Option<usize> {
+        let mut alive = (0..self.snakes.len()).filter(|&i| self.snakes[i].alive());
+
+        match (alive.next(), alive.next()) {
+            (Some(i), None) if self.snakes.len() > 1 => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Food currently on the board, all of it normal food.
+    pub fn food(&self) -> &[Food] {
+        &self.food
+    }
+
+    /// Number of ticks played so far.
+    pub fn tick(&self) -> usize {
+        self.tick
+    }
+
+    /// Rules the arena was created with.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Whether the arena is over.
+    pub fn finished(&self) -> bool {
+        let alive = self.snakes.iter().filter(|snake| snake.alive()).count();
+
+        alive == 0 || (alive == 1 && self.snakes.len() > 1)
+    }
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArenaError::FarFromHead => {
+                write!(
+                    f,
+                    "arena cannot place food far from the head of several snakes"
+                )
+            }
+            ArenaError::FoodKind(kind) => {
+                write!(f, "arena only places normal food, not {:?} food", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArenaError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::fixtures::food;
+    use crate::snake::food::FoodRules;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    impl Arena {
+        // Swaps in food on `cells`, keeping the board in sync
+        fn set_food(&mut self, cells: &[isize]) {
+            for old in std::mem::replace(&mut self.food, food(cells)) {
+                self.board.set_tile(old.cell, Tile::Empty);
+            }
+            for &cell in cells {
+                self.board.set_tile(cell, Tile::Food);
+            }
+        }
+    }
+
+    // Two snakes of length 2 on a 7x7 board, facing each other on the
+    // middle row
+    fn duel() -> Arena {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let spawns = [(22, Direction::Right), (26, Direction::Left)];
+
+        Arena::new(&mut rng, GameConfig::new(7, 7), &spawns).unwrap()
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let arena = duel();
+
+            assert_eq!(arena.len(), 2);
+            assert_eq!(arena.snake(0), &VecDeque::from([21, 22]));
+            assert_eq!(arena.snake(1), &VecDeque::from([27, 26]));
+            assert_eq!(arena.scores(), vec![0, 0]);
+            assert_eq!(arena.food().len(), 1);
+            assert!(![21, 22, 26, 27].contains(&arena.food()[0].cell));
+            assert!(!arena.finished());
+        }
+
+        #[test]
+        fn test_bodies_do_not_overlap() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = GameConfig {
+                start_length: 3,
+                ..GameConfig::new(7, 7)
+            };
+            let arena = Arena::new(
+                &mut rng,
+                config,
+                &[(22, Direction::Right), (20, Direction::Left)],
+            )
+            .unwrap();
+
+            assert_eq!(arena.snake(0), &VecDeque::from([21, 22]));
+            assert_eq!(arena.snake(1), &VecDeque::from([20]));
+        }
+
+        #[test]
+        fn test_no_room_for_food() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = GameConfig {
+                width: 2,
+                height: 1,
+                start_length: 1,
+                ..GameConfig::new(3, 3)
+            };
+            let arena = Arena::new(
+                &mut rng,
+                config,
+                &[(0, Direction::Left), (1, Direction::Right)],
+            )
+            .unwrap();
+
+            assert!(arena.food().is_empty());
+            assert!(arena.finished());
+            assert_eq!(arena.death_cause(0), Some(DeathCause::BoardFull));
+            assert_eq!(arena.eliminations()[1].tick, 0);
+        }
+
+        #[test]
+        fn test_unsupported() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let spawns = [(22, Direction::Right), (26, Direction::Left)];
+
+            let config = GameConfig {
+                food_spawn: FoodSpawn::FarFromHead,
+                ..GameConfig::new(7, 7)
+            };
+            assert_eq!(
+                Arena::new(&mut rng, config, &spawns).err(),
+                Some(ArenaError::FarFromHead)
+            );
+
+            let config = GameConfig {
+                food_rules: FoodRules {
+                    golden_weight: 1.0,
+                    ..FoodRules::default()
+                },
+                ..GameConfig::new(7, 7)
+            };
+            assert_eq!(
+                Arena::new(&mut rng, config, &spawns).err(),
+                Some(ArenaError::FoodKind(FoodKind::Golden))
+            );
+        }
+
+        #[test]
+        fn test_food_sequence() {
+            // Arenas sharing a food seed see food in the same places
+            let food = |seed: u64| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let config = GameConfig {
+                    food_spawn: FoodSpawn::Sequence { seed: 3 },
+                    food_count: 4,
+                    ..GameConfig::new(7, 7)
+                };
+                let spawns = [(22, Direction::Right), (26, Direction::Left)];
+
+                Arena::new(&mut rng, config, &spawns)
+                    .unwrap()
+                    .food()
+                    .to_vec()
+            };
+
+            assert_eq!(food(1), food(2));
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_shared_spawn() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            Arena::new(
+                &mut rng,
+                GameConfig::new(7, 7),
+                &[(22, Direction::Right), (22, Direction::Left)],
+            )
+            .unwrap();
+        }
+    }
+
+    mod move_snakes {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut arena = duel();
+            arena.set_food(&[0]);
+
+            let outcomes = arena.move_snakes(&[Direction::Up, Direction::Down]);
+
+            assert_eq!(outcomes, vec![StepOutcome::default(); 2]);
+            assert_eq!(arena.snake(0), &VecDeque::from([22, 15]));
+            assert_eq!(arena.snake(1), &VecDeque::from([26, 33]));
+            assert_eq!(arena.tick(), 1);
+        }
+
+        #[test]
+        fn test_head_to_head() {
+            let mut arena = duel();
+            arena.set_food(&[0]);
+
+            arena.move_snakes(&[Direction::Right, Direction::Left]);
+            let outcomes = arena.move_snakes(&[Direction::Right, Direction::Left]);
+
+            for (i, outcome) in outcomes.iter().enumerate() {
+                assert!(outcome.finished);
+                assert_eq!(outcome.death_cause, Some(DeathCause::HeadToHead));
+                assert_eq!(arena.death_cause(i), Some(DeathCause::HeadToHead));
+            }
+            assert!(arena.finished());
+            assert_eq!(arena.winner(), None);
+        }
+
+        #[test]
+        fn test_swap() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut arena = Arena::new(
+                &mut rng,
+                GameConfig::new(7, 7),
+                &[(23, Direction::Right), (24, Direction::Left)],
+            )
+            .unwrap();
+            arena.set_food(&[0]);
+
+            arena.move_snakes(&[Direction::Right, Direction::Left]);
+
+            assert_eq!(arena.death_cause(0), Some(DeathCause::SnakeCollision));
+            assert_eq!(arena.death_cause(1), Some(DeathCause::SnakeCollision));
+        }
+
+        #[test]
+        fn test_head_to_body() {
+            let mut arena = duel();
+            arena.set_food(&[0]);
+
+            // The second snake runs into the tail of the first
+            arena.move_snakes(&[Direction::Up, Direction::Up]);
+            arena.move_snakes(&[Direction::Right, Direction::Left]);
+            arena.move_snakes(&[Direction::Up, Direction::Left]);
+            let outcomes = arena.move_snakes(&[Direction::Up, Direction::Left]);
+
+            assert_eq!(outcomes[0].death_cause, None);
+            assert_eq!(outcomes[1].death_cause, Some(DeathCause::SnakeCollision));
+            assert!(outcomes[0].finished && outcomes[1].finished);
+            assert_eq!(arena.winner(), Some(0));
+        }
+
+        #[test]
+        fn test_contested_food() {
+            let mut arena = duel();
+            arena.set_food(&[24]);
+
+            arena.move_snakes(&[Direction::Right, Direction::Left]);
+            let outcomes = arena.move_snakes(&[Direction::Right, Direction::Left]);
+
+            assert!(outcomes.iter().all(|outcome| !outcome.ate_food));
+            assert_eq!(arena.scores(), vec![0, 0]);
+            assert_eq!(arena.food()[0].cell, 24);
+            assert_eq!(arena.food().len(), 1);
+        }
+
+        #[test]
+        fn test_eat() {
+            let mut arena = duel();
+            arena.set_food(&[15]);
+
+            let outcomes = arena.move_snakes(&[Direction::Up, Direction::Down]);
+
+            assert!(outcomes[0].ate_food);
+            assert_eq!(outcomes[0].score_delta, 1);
+            assert!(!outcomes[1].ate_food);
+            assert_eq!(arena.scores(), vec![1, 0]);
+            assert_eq!(arena.snake(0), &VecDeque::from([21, 22, 15]));
+            assert_eq!(arena.food().len(), 1);
+        }
+
+        #[test]
+        fn test_wall() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut arena = Arena::new(
+                &mut rng,
+                GameConfig::new(7, 7),
+                &[(3, Direction::Up), (24, Direction::Up), (45, Direction::Up)],
+            )
+            .unwrap();
+            arena.set_food(&[0]);
+
+            let outcomes = arena.move_snakes(&[Direction::Up; 3]);
+
+            assert_eq!(outcomes[0].death_cause, Some(DeathCause::TopBottomWall));
+            assert!(outcomes[0].finished);
+            assert!(!outcomes[1].finished && !outcomes[2].finished);
+            assert!(!arena.finished());
+        }
+
+        #[test]
+        fn test_eliminations() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut arena = Arena::new(
+                &mut rng,
+                GameConfig::new(7, 7),
+                &[(3, Direction::Up), (18, Direction::Up), (45, Direction::Up)],
+            )
+            .unwrap();
+            arena.set_food(&[48]);
+
+            arena.move_snakes(&[Direction::Up; 3]);
+            arena.move_snakes(&[Direction::Up; 3]);
+            let outcomes = arena.move_snakes(&[Direction::Up; 3]);
+
+            assert_eq!(
+                arena.eliminations(),
+                &[
+                    Elimination {
+                        snake: 0,
+                        tick: 1,
+                        cause: DeathCause::TopBottomWall,
+                    },
+                    Elimination {
+                        snake: 1,
+                        tick: 3,
+                        cause: DeathCause::TopBottomWall,
+                    },
+                ]
+            );
+            assert!(outcomes.iter().all(|outcome| outcome.finished));
+            assert_eq!(arena.winner(), Some(2));
+
+            // Ticks after the arena is over are ignored
+            arena.move_snakes(&[Direction::Up; 3]);
+            assert_eq!(arena.tick(), 3);
+        }
+
+        #[test]
+        fn test_eliminated_snakes_leave_the_board() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut arena = Arena::new(
+                &mut rng,
+                GameConfig::new(7, 7),
+                &[
+                    (1, Direction::Up),
+                    (15, Direction::Down),
+                    (40, Direction::Up),
+                ],
+            )
+            .unwrap();
+            arena.set_food(&[48]);
+
+            // The first snake dies on the top wall, so the second may
+            // move into the cell its body was on
+            arena.move_snakes(&[Direction::Up, Direction::Right, Direction::Up]);
+            arena.move_snakes(&[Direction::Up, Direction::Up, Direction::Up]);
+            let outcomes = arena.move_snakes(&[Direction::Up, Direction::Left, Direction::Up]);
+
+            assert_eq!(outcomes[1].death_cause, None);
+            assert_eq!(arena.head(1), 8);
+        }
+    }
+
+    mod board {
+        use super::*;
+        use rand::Rng;
+
+        // Checks every tile against the live snakes, food and walls
+        fn assert_in_sync(arena: &Arena) {
+            let area = arena.config.width * arena.config.height;
+
+            for cell in 0..area {
+                let expected = if arena.config.walls.contains(&cell) {
+                    Tile::Wall
+                } else if (0..arena.len()).any(|i| arena.alive(i) && arena.snake(i).contains(&cell))
+                {
+                    Tile::Snake
+                } else if arena.food().iter().any(|food| food.cell == cell) {
+                    Tile::Food
+                } else {
+                    Tile::Empty
+                };
+                assert_eq!(arena.board.tile(cell), expected, "cell {}", cell);
+            }
+        }
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = GameConfig {
+                walls: [10, 24, 38].into(),
+                food_count: 3,
+                start_length: 3,
+                ..GameConfig::new(7, 7)
+            };
+            let spawns = [
+                (8, Direction::Right),
+                (40, Direction::Left),
+                (26, Direction::Up),
+            ];
+
+            for _ in 0..20 {
+                let mut arena = Arena::new(&mut rng, config.clone(), &spawns).unwrap();
+                assert_in_sync(&arena);
+
+                while !arena.finished() {
+                    let directions: Vec<Direction> = (0..arena.len())
+                        .map(|_| Direction::ALL[rng.gen_range(0..4)])
+                        .collect();
+                    arena.move_snakes(&directions);
+                    assert_in_sync(&arena);
+                }
+            }
+        }
+    }
+
+    mod hunger {
+        use super::*;
+        use crate::snake::hunger::{Hunger, HungerRefill};
+
+        #[test]
+        fn test_grow() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = GameConfig {
+                hunger: Some(Hunger::new(5, HungerRefill::Grow(10))),
+                ..GameConfig::new(7, 7)
+            };
+            let spawns = [(22, Direction::Right), (26, Direction::Left)];
+            let mut arena = Arena::new(&mut rng, config.clone(), &spawns).unwrap();
+            arena.set_food(&[15]);
+
+            arena.move_snakes(&[Direction::Up, Direction::Down]);
+
+            // Only the snake that ate has its limit grown
+            assert_eq!(arena.moves_left(0), Some(15));
+            assert_eq!(arena.moves_left(1), Some(4));
+            assert_eq!(arena.config(), &config);
+        }
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut arena = Arena::new(
+                &mut rng,
+                GameConfig::new(3, 3),
+                &[(1, Direction::Right), (7, Direction::Left)],
+            )
+            .unwrap();
+            arena.set_food(&[4]);
+
+            assert_eq!(
+                arena.display(),
+                "|---------|\n| a  A    |\n|    \u{2022}    |\n|    B  b |\n|---------|\nScores: A: 0, B: 0"
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::snake::config::GameConfig;
+use crate::snake::direction::Direction;
+use crate::snake::game::{Game, StepOutcome};
+
+/// Packed results of stepping every game in a [`GameBatch`].
+///
+/// Game `i` owns the `i`th entry of each slice, or the `i`th chunk of
+/// [`observations`](BatchStep::observations).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BatchStep<'a> {
+    /// Observations of every game after the step, one chunk per game.
+    pub observations: &'a [f32],
+    /// Change in score of every game on the step.
+    pub rewards: &'a [f32],
+    /// Whether each game finished on the step, or had already finished.
+    pub dones: &'a [bool],
+    /// What happened in each game on the step.
+    pub outcomes: &'a [StepOutcome],
+}
+
+/// Several independent games with the same rules, stepped in lockstep.
+///
+/// Observations, rewards and outcomes are written into buffers owned by the
+/// batch and reused on every step. With auto reset on, a game that finishes
+/// is replaced by a fresh one straight away and the observation for that
+/// step is of the fresh game, while its reward and outcome are still those
+/// of the move that ended the old one.
+pub struct GameBatch {
+    rng: ChaCha8Rng,
+    config: GameConfig,
+    games: Vec<Game>,
+    auto_reset: bool,
+    observations: Vec<f32>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    outcomes: Vec<StepOutcome>,
+}
+
+impl GameBatch {
+    /// Creates `size` games following `config`, each observed through the
+    /// observation encoding of `config`.
+    ///
+    /// `rng` seeds the batch's own generator, which seeds every game it
+    /// creates, so batches created from identically seeded generators play
+    /// out identically.
+    pub fn new(
+        rng: &mut dyn rand::RngCore,
+        config: GameConfig,
+        size: usize,
+        auto_reset: bool,
+    ) -> GameBatch {
+        assert!(size > 0);
+
+        let mut batch = GameBatch {
+            rng: ChaCha8Rng::from_seed(rng.gen()),
+            games: Vec::with_capacity(size),
+            observations: Vec::new(),
+            rewards: vec![0.0; size],
+            dones: vec![false; size],
+            outcomes: vec![StepOutcome::default(); size],
+            config,
+            auto_reset,
+        };
+        batch.reset();
+
+        batch
+    }
+
+    /// Replaces every game with a fresh one and returns their observations.
+    pub fn reset(&mut self) -> &[f32] {
+        let size = self.rewards.len();
+
+        self.games.clear();
+        for _ in 0..size {
+            let game = Game::from_config(&mut self.rng, self.config.clone());
+            self.games.push(game);
+        }
+
+        let len = self.observation_size();
+        self.observations.resize(size * len, 0.0);
+        let chunks = self.observations.chunks_mut(len);
+        for (game, out) in self.games.iter().zip(chunks) {
+            game.observer().observe(game, out);
+        }
+        self.rewards.fill(0.0);
+        self.dones.fill(false);
+        self.outcomes.fill(StepOutcome::default());
+
+        &self.observations
+    }
+
+    /// Moves the snake of game `i` in `directions[i]`, for every game at
+    /// once. Games that have finished and were not reset are left as they
+    /// are, with no reward.
+    pub fn step(&mut self, directions: &[Direction]) -> BatchStep<'_> {
+        assert_eq!(directions.len(), self.games.len());
+
+        let len = self.observation_size();
+        let chunks = self.observations.chunks_mut(len);
+        for (i, out) in chunks.enumerate() {
+            let game = &mut self.games[i];
+            let outcome = game.move_snake(directions[i]);
+
+            if outcome.finished && self.auto_reset {
+                *game = Game::from_config(&mut self.rng, self.config.clone());
+            }
+
+            game.observer().observe(game, out);
+            self.rewards[i] = outcome.score_delta as f32;
+            self.dones[i] = outcome.finished;
+            self.outcomes[i] = outcome;
+        }
+
+        BatchStep {
+            observations: &self.observations,
+            rewards: &self.rewards,
+            dones: &self.dones,
+            outcomes: &self.outcomes,
+        }
+    }
+
+    /// Observations of every game, one chunk per game.
+    pub fn observations(&self) -> &[f32] {
+        &self.observations
+    }
+
+    /// Number of values observed for each game.
+    pub fn observation_size(&self) -> usize {
+        self.games[0].observer().size()
+    }
+
+    /// Games in the batch.
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// Number of games in the batch.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Whether the batch has no games, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::game::DeathCause;
+    use crate::snake::observation::{Observer, TileObserver};
+
+    fn new_batch(size: usize, auto_reset: bool) -> GameBatch {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        GameBatch::new(&mut rng, GameConfig::new(5, 5), size, auto_reset)
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let batch = new_batch(3, false);
+
+            assert_eq!(batch.len(), 3);
+            assert_eq!(batch.observation_size(), 25);
+            assert_eq!(batch.observations().len(), 75);
+
+            let observer = TileObserver::new(5, 5);
+            for (game, chunk) in batch.games().iter().zip(batch.observations().chunks(25)) {
+                assert_eq!(observer.observation(game), chunk);
+            }
+        }
+
+        #[test]
+        fn test_games_differ() {
+            let batch = new_batch(8, false);
+            let food: Vec<isize> = batch.games().iter().map(|g| g.food()[0].cell).collect();
+
+            assert!(food.iter().any(|&cell| cell != food[0]));
+        }
+    }
+
+    mod step {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut batch = new_batch(2, false);
+            let step = batch.step(&[Direction::Up, Direction::Left]);
+
+            assert_eq!(step.dones, &[false, false]);
+            assert_eq!(step.observations.len(), 50);
+            assert_eq!(batch.games()[0].head(), 7);
+            assert_eq!(batch.games()[1].head(), 11);
+
+            let observer = TileObserver::new(5, 5);
+            assert_eq!(
+                observer.observation(&batch.games()[1]),
+                &batch.observations()[25..]
+            );
+        }
+
+        #[test]
+        fn test_finished_games_stay_done() {
+            let mut batch = new_batch(2, false);
+
+            batch.step(&[Direction::Up, Direction::Down]);
+            batch.step(&[Direction::Up, Direction::Down]);
+            let step = batch.step(&[Direction::Up, Direction::Down]);
+            assert_eq!(step.dones, &[true, true]);
+            assert_eq!(
+                step.outcomes[0].death_cause,
+                Some(DeathCause::TopBottomWall)
+            );
+
+            let step = batch.step(&[Direction::Up, Direction::Down]);
+            assert_eq!(step.dones, &[true, true]);
+            assert_eq!(step.rewards, &[0.0, 0.0]);
+            assert_eq!(step.outcomes[0].death_cause, None);
+        }
+
+        #[test]
+        fn test_auto_reset() {
+            let mut batch = new_batch(2, true);
+
+            batch.step(&[Direction::Up, Direction::Right]);
+            batch.step(&[Direction::Up, Direction::Right]);
+            let step = batch.step(&[Direction::Up, Direction::Right]);
+            assert_eq!(step.dones, &[true, true]);
+
+            // The finished games were replaced with fresh ones
+            assert!(batch.games().iter().all(|game| !game.finished()));
+            assert_eq!(batch.games()[0].head(), 12);
+
+            let step = batch.step(&[Direction::Up, Direction::Right]);
+            assert_eq!(step.dones, &[false, false]);
+        }
+
+        #[test]
+        fn test_rewards() {
+            let mut batch = new_batch(1, false);
+            let food = batch.games()[0].food()[0].cell;
+            let head = batch.games()[0].head();
+
+            // Walk to the food along the row, then the column
+            let mut directions = Vec::new();
+            let (dx, dy) = (food % 5 - head % 5, food / 5 - head / 5);
+            for _ in 0..dx.abs() {
+                directions.push(if dx > 0 {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                });
+            }
+            for _ in 0..dy.abs() {
+                directions.push(if dy > 0 {
+                    Direction::Down
+                } else {
+                    Direction::Up
+                });
+            }
+
+            let mut rewards = Vec::new();
+            for direction in directions {
+                rewards.push(batch.step(&[direction]).rewards[0]);
+            }
+
+            assert_eq!(rewards.pop(), Some(1.0));
+            assert!(rewards.iter().all(|&reward| reward == 0.0));
+        }
+    }
+}
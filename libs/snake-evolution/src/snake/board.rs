@@ -0,0 +1,381 @@
+use std::collections::VecDeque;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::snake::config::GameConfig;
+use crate::snake::direction::Direction;
+use crate::snake::food::{Food, FoodKind, FoodSpawn};
+use crate::snake::game::Tile;
+use crate::snake::hunger::Hunger;
+
+// Tiles of a board shared by the game and the arena, with a list of the empty
+// cells so collisions, food placement and rendering don't need to search the
+// snakes
+pub(crate) struct Board {
+    // Places every piece of food and picks its kind
+    rng: ChaCha8Rng,
+    food_sequence: Option<ChaCha8Rng>,
+    width: isize,
+    grid: Vec<Tile>,
+    // Empty cells in no particular order, and each cell's position in it
+    free: Vec<isize>,
+    slots: Vec<usize>,
+}
+
+// A snake on a board: its cells from tail to head, the cells it has still to
+// grow by and its own hunger budget, as eating may grow its limit
+pub(crate) struct Body {
+    pub(crate) cells: VecDeque<isize>,
+    pub(crate) pending_growth: usize,
+    pub(crate) hunger: Option<Hunger>,
+    pub(crate) moves_left: usize,
+}
+
+impl Body {
+    pub(crate) fn head(&self) -> isize {
+        *self.cells.back().unwrap()
+    }
+
+    pub(crate) fn moves_left(&self) -> Option<usize> {
+        self.hunger.map(|_| self.moves_left)
+    }
+
+    // Grows the snake by `growth` cells over its next moves and refills its
+    // hunger budget
+    pub(crate) fn feed(&mut self, growth: usize) {
+        self.pending_growth += growth;
+
+        if let Some(hunger) = &mut self.hunger {
+            self.moves_left = hunger.refill(self.moves_left);
+        }
+    }
+
+    // Counts a move made without eating against the hunger budget
+    // Returns true once the snake has run out of moves
+    pub(crate) fn go_hungry(&mut self) -> bool {
+        if self.hunger.is_none() {
+            return false;
+        }
+
+        self.moves_left -= 1;
+        self.moves_left == 0
+    }
+}
+
+impl Board {
+    // Board holding the walls of `config`, with its generator seeded from
+    // `rng`
+    pub(crate) fn new(rng: &mut dyn rand::RngCore, config: &GameConfig) -> Board {
+        let area = config.width * config.height;
+
+        let mut board = Board {
+            rng: ChaCha8Rng::from_seed(rng.gen()),
+            food_sequence: match config.food_spawn {
+                FoodSpawn::Sequence { seed } => Some(ChaCha8Rng::seed_from_u64(seed)),
+                _ => None,
+            },
+            width: config.width,
+            grid: vec![Tile::Empty; area as usize],
+            free: (0..area).collect(),
+            slots: (0..area as usize).collect(),
+        };
+        for &wall in &config.walls {
+            board.set_tile(wall, Tile::Wall);
+        }
+
+        board
+    }
+
+    pub(crate) fn tile(&self, cell: isize) -> Tile {
+        self.grid[cell as usize]
+    }
+
+    pub(crate) fn free_cells(&self) -> &[isize] {
+        &self.free
+    }
+
+    // Changes the tile on `cell`, keeping the list of empty cells in sync
+    pub(crate) fn set_tile(&mut self, cell: isize, tile: Tile) {
+        let i = cell as usize;
+
+        match (self.grid[i] == Tile::Empty, tile == Tile::Empty) {
+            (true, false) => {
+                // The last empty cell takes over this cell's slot
+                let slot = self.slots[i];
+                self.free.swap_remove(slot);
+                if let Some(&moved) = self.free.get(slot) {
+                    self.slots[moved as usize] = slot;
+                }
+            }
+            (false, true) => {
+                self.slots[i] = self.free.len();
+                self.free.push(cell);
+            }
+            _ => {}
+        }
+
+        self.grid[i] = tile;
+    }
+
+    // Puts the head of a new snake on `cell` and lays its body out behind
+    // it, away from `heading`, over empty cells
+    // Whatever does not fit on the board, or the whole body without a
+    // heading, is grown over the first moves
+    pub(crate) fn spawn(
+        &mut self,
+        config: &GameConfig,
+        cell: isize,
+        heading: Option<Direction>,
+    ) -> Body {
+        let mut cells = VecDeque::from([cell]);
+        self.set_tile(cell, Tile::Snake);
+
+        if let Some(heading) = heading {
+            let behind = heading.opposite();
+
+            while cells.len() < config.start_length {
+                let tail = *cells.front().unwrap();
+
+                match config.neighbour(tail, behind) {
+                    Some(cell) if self.tile(cell) == Tile::Empty => {
+                        cells.push_front(cell);
+                        self.set_tile(cell, Tile::Snake);
+                    }
+                    _ => break,
+                }
+            }
+        }
+
+        Body {
+            pending_growth: config.start_length - cells.len(),
+            cells,
+            hunger: config.hunger,
+            moves_left: config.hunger.map_or(0, |hunger| hunger.limit()),
+        }
+    }
+
+    // Moves the head of `body` onto `cell`
+    pub(crate) fn move_body(&mut self, body: &mut Body, cell: isize) {
+        body.cells.push_back(cell);
+        self.set_tile(cell, Tile::Snake);
+
+        // Leave the tail in place while the snake is still growing
+        if body.pending_growth > 0 {
+            body.pending_growth -= 1;
+        } else {
+            let tail = body.cells.pop_front().unwrap();
+            self.set_tile(tail, Tile::Empty);
+        }
+    }
+
+    // Takes up to `cells` cells off the tail of `body`, keeping its head
+    pub(crate) fn shrink(&mut self, body: &mut Body, cells: usize) {
+        for _ in 0..cells.min(body.cells.len() - 1) {
+            let tail = body.cells.pop_front().unwrap();
+            self.set_tile(tail, Tile::Empty);
+        }
+    }
+
+    // Places food until `food` holds as many pieces as `config` asks for,
+    // measuring distances for far spawns from `head`
+    // Returns false if no food is left on the board
+    pub(crate) fn top_up(
+        &mut self,
+        config: &GameConfig,
+        food: &mut Vec<Food>,
+        head: Option<isize>,
+    ) -> bool {
+        while food.len() < config.food_count {
+            match self.place_food(config, head) {
+                Some(piece) => food.push(piece),
+                None => break,
+            }
+        }
+
+        !food.is_empty()
+    }
+
+    // Places a single piece of food following the spawn policy and food rules
+    // of `config`
+    // Returns `None` if there is no free cell left
+    fn place_food(&mut self, config: &GameConfig, head: Option<isize>) -> Option<Food> {
+        let area = self.grid.len() as isize;
+
+        if self.free.is_empty() {
+            return None;
+        }
+
+        let cell = match config.food_spawn {
+            FoodSpawn::Uniform => *self.free.choose(&mut self.rng).unwrap(),
+            // Ranks every empty cell, so this one does scale with the board
+            FoodSpawn::FarFromHead => {
+                let head = head.expect("far food spawns need a head to measure from");
+                let distances: Vec<isize> = self
+                    .free
+                    .iter()
+                    .map(|&i| config.distance(head, i))
+                    .collect();
+                let furthest = *distances.iter().max().unwrap();
+
+                let far_squares: Vec<isize> = self
+                    .free
+                    .iter()
+                    .zip(distances)
+                    .filter(|(_, distance)| distance * 2 >= furthest)
+                    .map(|(&i, _)| i)
+                    .collect();
+
+                *far_squares.choose(&mut self.rng).unwrap()
+            }
+            FoodSpawn::Sequence { .. } => {
+                let sequence = self.food_sequence.as_mut().unwrap();
+
+                loop {
+                    let i = sequence.gen_range(0..area);
+
+                    if self.grid[i as usize] == Tile::Empty {
+                        break i;
+                    }
+                }
+            }
+        };
+
+        let rules = config.food_rules;
+        let kind = rules
+            .weights()
+            .choose_weighted(&mut self.rng, |(_, weight)| *weight)
+            .unwrap()
+            .0;
+
+        self.set_tile(cell, Tile::Food);
+        Some(Food {
+            cell,
+            kind,
+            ticks_left: (kind == FoodKind::Timed).then_some(rules.timed_ticks),
+        })
+    }
+
+    // Draws the board as text, filling in walls and empty cells, with `glyph`
+    // giving the character shown on every snake and food cell
+    pub(crate) fn display(&self, glyph: impl Fn(isize) -> char) -> String {
+        let width = self.width;
+
+        let mut out = String::from("|");
+        for _ in 0..width {
+            out += "---"
+        }
+
+        let range = (0..self.grid.len() as isize).step_by(width.try_into().unwrap());
+        for y in range {
+            out += "|\n|";
+            for x in 0..width {
+                let i = y + x;
+                match self.tile(i) {
+                    Tile::Wall => out += "\u{2592}\u{2592}\u{2592}",
+                    Tile::Empty => out += "   ",
+                    Tile::Snake | Tile::Food => out += &format!(" {} ", glyph(i)),
+                }
+            }
+        }
+
+        out += "|\n|";
+        for _ in 0..width {
+            out += "---"
+        }
+        out += "|\n";
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Board {
+        // Position of every cell in the list of empty cells, for checking
+        // the two stay in sync
+        pub(crate) fn slots(&self) -> &[usize] {
+            &self.slots
+        }
+    }
+
+    fn new_board() -> Board {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let config = GameConfig {
+            walls: [0, 4].into(),
+            ..GameConfig::new(3, 3)
+        };
+
+        Board::new(&mut rng, &config)
+    }
+
+    mod set_tile {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut board = new_board();
+            assert_eq!(board.free_cells().len(), 7);
+
+            board.set_tile(5, Tile::Snake);
+            board.set_tile(0, Tile::Empty);
+            board.set_tile(8, Tile::Food);
+
+            let mut free = board.free_cells().to_vec();
+            free.sort_unstable();
+            assert_eq!(free, vec![0, 1, 2, 3, 6, 7]);
+            for (slot, &cell) in board.free_cells().iter().enumerate() {
+                assert_eq!(board.slots()[cell as usize], slot);
+            }
+        }
+    }
+
+    mod spawn {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut board = new_board();
+            let config = GameConfig {
+                start_length: 3,
+                ..GameConfig::new(3, 3)
+            };
+
+            // The wall on the centre cell stops the body short
+            let body = board.spawn(&config, 5, Some(Direction::Right));
+            assert_eq!(body.cells, VecDeque::from([5]));
+            assert_eq!(body.pending_growth, 2);
+            assert_eq!(board.tile(4), Tile::Wall);
+
+            let body = board.spawn(&config, 6, Some(Direction::Down));
+            assert_eq!(body.cells, VecDeque::from([3, 6]));
+            assert_eq!(body.pending_growth, 1);
+            assert_eq!(board.tile(3), Tile::Snake);
+
+            let body = board.spawn(&config, 8, None);
+            assert_eq!(body.cells, VecDeque::from([8]));
+            assert_eq!(body.pending_growth, 2);
+        }
+    }
+
+    mod place_food {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut board = new_board();
+            let config = GameConfig::new(3, 3);
+
+            let mut cells: Vec<isize> = (0..7)
+                .map(|_| board.place_food(&config, None).unwrap().cell)
+                .collect();
+            cells.sort_unstable();
+
+            assert_eq!(cells, vec![1, 2, 3, 5, 6, 7, 8]);
+            assert_eq!(board.place_food(&config, None), None);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use lib_genetic_algorithm::Individual;
+use lib_neural_network::Network;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::snake::agent::SnakeAgent;
+use crate::snake::save::{self, SaveError};
+use crate::snake::train::{GenerationStats, TrainConfig};
+
+/// Where a training run writes its results.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Output {
+    /// A directory holding the statistics of every generation and the best
+    /// agent.
+    Directory(PathBuf),
+    /// A file holding only the best agent.
+    Model(PathBuf),
+}
+
+/// Snapshot of a training run between two generations, taken with
+/// [`Trainer::checkpoint`](crate::snake::train::Trainer::checkpoint).
+///
+/// It holds the whole state of the run, down to its random number generator,
+/// so a run resumed from it evolves exactly as if it had never stopped.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Parameters of the run.
+    pub config: TrainConfig,
+    /// Generator everything random in the run is drawn from.
+    pub rng: ChaCha8Rng,
+    /// Agents of the generation that will be evaluated next.
+    pub population: Vec<SnakeAgent>,
+    /// Number of generations evolved so far.
+    pub generation: usize,
+    /// Fittest agent of any generation evaluated so far.
+    pub best: Option<SnakeAgent>,
+    /// Summaries of every generation evolved so far.
+    pub history: Vec<GenerationStats>,
+    /// Where the run writes its results, so a resumed run writes them to the
+    /// same place, or `None` if it keeps them to itself.
+    pub output: Option<Output>,
+}
+
+impl Checkpoint {
+    /// Reads the checkpoint saved at `path`, checking that its agents fit
+    /// its config.
+    pub fn load(path: impl AsRef<Path>) -> Result<Checkpoint, SaveError> {
+        let checkpoint: Checkpoint = save::load(path)?;
+        checkpoint.validate()?;
+
+        Ok(checkpoint)
+    }
+
+    /// Writes the checkpoint to `path`.
+    ///
+    /// The checkpoint is written next to `path` first and then moved over
+    /// it, so a run stopped while saving keeps its previous checkpoint.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+
+        save::save(self, &partial)?;
+        std::fs::rename(&partial, path).map_err(SaveError::Io)
+    }
+
+    fn validate(&self) -> Result<(), SaveError> {
+        self.config
+            .validate()
+            .map_err(|err| SaveError::Invalid(format!("config.{}", err)))?;
+
+        if self.population.len() != self.config.population {
+            return Err(SaveError::Invalid(format!(
+                "population has {} agents, but the config asks for {}",
+                self.population.len(),
+                self.config.population
+            )));
+        }
+        if self.history.len() != self.generation {
+            return Err(SaveError::Invalid(format!(
+                "history covers {} generations, but {} were evolved",
+                self.history.len(),
+                self.generation
+            )));
+        }
+
+        let genes = Network::weight_count(&self.config.topology());
+        let agents = self.population.iter().chain(&self.best);
+        if agents
+            .into_iter()
+            .any(|agent| agent.chromosome().len() != genes)
+        {
+            return Err(SaveError::Invalid(format!(
+                "agents must have {} genes to fit the config's network",
+                genes
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::train::Trainer;
+
+    fn config() -> TrainConfig {
+        TrainConfig {
+            population: 8,
+            hidden: vec![4],
+            games: 1,
+            max_steps: 50,
+            ..TrainConfig::default()
+        }
+    }
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("snake-checkpoint-{}-{}", std::process::id(), name))
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut trainer = Trainer::new(&config());
+            trainer.evolve();
+            let path = path("round-trip");
+
+            let saved = Checkpoint {
+                output: Some(Output::Directory(PathBuf::from("runs/test"))),
+                ..trainer.checkpoint()
+            };
+            saved.save(&path).unwrap();
+            let checkpoint = Checkpoint::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(checkpoint, saved);
+
+            // The saved generator carries on from where it was
+            let mut resumed = Trainer::resume(checkpoint);
+            assert_eq!(resumed.evolve(), trainer.evolve());
+            assert_eq!(resumed.population(), trainer.population());
+        }
+
+        #[test]
+        fn test_errors() {
+            let path = path("errors");
+
+            let mut checkpoint = Trainer::new(&config()).checkpoint();
+            checkpoint.population.pop();
+            checkpoint.save(&path).unwrap();
+            assert!(matches!(
+                Checkpoint::load(&path),
+                Err(SaveError::Invalid(_))
+            ));
+
+            let mut checkpoint = Trainer::new(&config()).checkpoint();
+            checkpoint.config.hidden = vec![5];
+            checkpoint.save(&path).unwrap();
+            assert!(matches!(
+                Checkpoint::load(&path),
+                Err(SaveError::Invalid(_))
+            ));
+
+            let mut checkpoint = Trainer::new(&config()).checkpoint();
+            checkpoint.generation = 1;
+            checkpoint.save(&path).unwrap();
+            assert!(matches!(
+                Checkpoint::load(&path),
+                Err(SaveError::Invalid(_))
+            ));
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::snake::direction::{ActionSpace, Direction};
+use crate::snake::food::{FoodRules, FoodSpawn};
+use crate::snake::game::{Boundary, DeathCause, ReversalPolicy};
+use crate::snake::hunger::Hunger;
+use crate::snake::level::Level;
+use crate::snake::observation::{Encoding, Observer};
+
+/// Rules and starting conditions for a [`Game`](crate::snake::game::Game).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    /// Number of columns on the board.
+    pub width: isize,
+    /// Number of rows on the board.
+    pub height: isize,
+    /// What happens when the snake moves off an edge of the board.
+    pub boundary: Boundary,
+    /// Cells holding a wall inside the board.
+    pub walls: BTreeSet<isize>,
+    /// Moves the snake may make without eating, or `None` for no limit.
+    ///
+    /// Files write no limit as `hunger = "off"`.
+    #[serde(with = "crate::snake::hunger::optional")]
+    pub hunger: Option<Hunger>,
+    /// Length of the snake at the start of the game.
+    pub start_length: usize,
+    /// Cell the head of the snake starts on, or `None` for the centre.
+    pub spawn: Option<isize>,
+    /// Direction the snake starts facing, or `None` for no heading.
+    ///
+    /// With a heading the body is laid out behind the head, otherwise the
+    /// snake starts as a single cell and grows to its starting length over
+    /// its first moves.
+    pub heading: Option<Direction>,
+    /// Cells the snake grows by for each food eaten.
+    pub growth: usize,
+    /// What happens when the snake is told to move back the way it came.
+    pub reversal: ReversalPolicy,
+    /// Number of food items on the board at once.
+    pub food_count: usize,
+    /// Where new food is placed on the board.
+    pub food_spawn: FoodSpawn,
+    /// How often each kind of food spawns and what it does when eaten.
+    pub food_rules: FoodRules,
+    /// How the game is observed when driven as an
+    /// [`Environment`](crate::snake::env::Environment).
+    pub observation: Encoding,
+    /// Which kind of move actions stand for when driven as an
+    /// [`Environment`](crate::snake::env::Environment).
+    pub actions: ActionSpace,
+}
+
+impl GameConfig {
+    /// Classic rules on an empty `width` by `height` board. Both dimensions
+    /// are enlarged to at least 3.
+    pub fn new(width: isize, height: isize) -> GameConfig {
+        GameConfig {
+            width: width.max(3),
+            height: height.max(3),
+            boundary: Boundary::Walls,
+            walls: BTreeSet::new(),
+            hunger: None,
+            start_length: 2,
+            spawn: None,
+            heading: None,
+            growth: 1,
+            reversal: ReversalPolicy::Die,
+            food_count: 1,
+            food_spawn: FoodSpawn::Uniform,
+            food_rules: FoodRules::default(),
+            observation: Encoding::Tiles,
+            actions: ActionSpace::Absolute,
+        }
+    }
+
+    /// Classic rules on the board described by `level`, starting on its start
+    /// cell and heading.
+    pub fn from_level(level: &Level) -> GameConfig {
+        GameConfig {
+            width: level.width(),
+            height: level.height(),
+            walls: level.walls().clone(),
+            spawn: Some(level.start()),
+            heading: level.heading(),
+            ..GameConfig::new(level.width(), level.height())
+        }
+    }
+
+    /// Checks that the rules describe a game that can be started, explaining
+    /// the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width < 1 || self.height < 1 {
+            return Err("width and height must be at least 1".to_string());
+        }
+        let area = self
+            .width
+            .checked_mul(self.height)
+            .ok_or_else(|| "width and height are too large".to_string())?;
+        if self.start_length == 0 {
+            return Err("start_length must be at least 1".to_string());
+        }
+        if self.food_count == 0 {
+            return Err("food_count must be at least 1".to_string());
+        }
+        if let Some(wall) = self.walls.iter().find(|wall| !(0..area).contains(wall)) {
+            return Err(format!("walls include cell {}, off the board", wall));
+        }
+        let spawn = self.spawn_cell();
+        if !(0..area).contains(&spawn) || self.walls.contains(&spawn) {
+            return Err(format!("spawn cell {} is off the board or a wall", spawn));
+        }
+        if self.hunger.is_some_and(|hunger| hunger.limit() == 0) {
+            return Err("hunger limit must be at least 1".to_string());
+        }
+
+        let weights = self.food_rules.weights();
+        if weights
+            .iter()
+            .any(|(_, weight)| !weight.is_finite() || *weight < 0.0)
+            || weights.iter().all(|(_, weight)| *weight == 0.0)
+        {
+            return Err(
+                "food_rules weights must be finite and not negative, and at least one must be positive"
+                    .to_string(),
+            );
+        }
+
+        if self.food_rules.timed_weight > 0.0 && self.food_rules.timed_ticks == 0 {
+            return Err(
+                "food_rules timed_ticks must be at least 1 when timed food spawns".to_string(),
+            );
+        }
+
+        match &self.observation {
+            Encoding::Rays(vision)
+                if vision.rays().is_empty() || vision.rays().contains(&(0, 0)) =>
+            {
+                Err("observation rays must be non-empty, with no (0, 0) ray".to_string())
+            }
+            Encoding::Grid {
+                crop: Some(radius), ..
+            } if *radius < 0 => Err("observation crop must not be negative".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Observer for games following these rules, through their observation
+    /// encoding.
+    pub fn observer(&self) -> Box<dyn Observer> {
+        self.observation.observer(self.width, self.height)
+    }
+
+    /// Cell the head of the snake starts on.
+    pub fn spawn_cell(&self) -> isize {
+        // Even dimensions round towards the top left
+        self.spawn
+            .unwrap_or(self.width * ((self.height - 1) / 2) + (self.width - 1) / 2)
+    }
+
+    // Cell next to `cell` in `direction`, if it is on the board
+    pub(crate) fn neighbour(&self, cell: isize, direction: Direction) -> Option<isize> {
+        let (x, y) = direction.value();
+        let (x, y) = (cell % self.width + x, cell / self.width + y);
+
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    // Cell a head on `cell` moves to in `direction`, following the boundary
+    // Moving off a walled edge gives the wall it hit instead
+    pub(crate) fn advance(&self, cell: isize, direction: Direction) -> Result<isize, DeathCause> {
+        if let Some(next) = self.neighbour(cell, direction) {
+            return Ok(next);
+        }
+
+        let horizontal = matches!(direction, Direction::Left | Direction::Right);
+        match self.boundary {
+            Boundary::Walls if horizontal => Err(DeathCause::LeftRightWall),
+            Boundary::Walls => Err(DeathCause::TopBottomWall),
+            Boundary::Wrap => {
+                let (x, y) = direction.value();
+                let x = (cell % self.width + x).rem_euclid(self.width);
+                let y = (cell / self.width + y).rem_euclid(self.height);
+
+                Ok(y * self.width + x)
+            }
+        }
+    }
+
+    // Direction a snake of `length` cells facing `heading` moves in when told
+    // to move in `direction`, following the reversal policy
+    // Gives `None` if the move is skipped
+    pub(crate) fn steer(
+        &self,
+        heading: Option<Direction>,
+        direction: Direction,
+        length: usize,
+    ) -> Option<Direction> {
+        match heading {
+            Some(heading) if direction == heading.opposite() && length > 1 => match self.reversal {
+                ReversalPolicy::Die => Some(direction),
+                ReversalPolicy::Ignore => None,
+                ReversalPolicy::ContinueStraight => Some(heading),
+            },
+            _ => Some(direction),
+        }
+    }
+
+    // Number of moves between cells `a` and `b` on an empty board, taking
+    // the shortest way around when the board wraps
+    pub(crate) fn distance(&self, a: isize, b: isize) -> isize {
+        let dx = (a % self.width - b % self.width).abs();
+        let dy = (a / self.width - b / self.width).abs();
+
+        match self.boundary {
+            Boundary::Walls => dx + dy,
+            Boundary::Wrap => dx.min(self.width - dx) + dy.min(self.height - dy),
+        }
+    }
+}
+
+impl Default for GameConfig {
+    /// Classic rules on an empty 10x10 board.
+    fn default() -> GameConfig {
+        GameConfig::new(10, 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod spawn_cell {
+        use super::*;
+
+        #[test]
+        fn test() {
+            assert_eq!(GameConfig::new(10, 10).spawn_cell(), 44);
+            assert_eq!(GameConfig::new(3, 3).spawn_cell(), 4);
+            assert_eq!(GameConfig::new(5, 3).spawn_cell(), 7);
+
+            let config = GameConfig {
+                spawn: Some(12),
+                ..GameConfig::new(5, 5)
+            };
+            assert_eq!(config.spawn_cell(), 12);
+        }
+
+        #[test]
+        fn test_from_level() {
+            let level = "..#\n.>.\n".parse().unwrap();
+            let config = GameConfig::from_level(&level);
+
+            assert_eq!((config.width, config.height), (3, 2));
+            assert_eq!(config.spawn_cell(), 4);
+            assert_eq!(config.heading, Some(Direction::Right));
+            assert_eq!(config.walls, BTreeSet::from([2]));
+        }
+    }
+
+    mod observer {
+        use super::*;
+        use crate::snake::observation::RayVision;
+
+        #[test]
+        fn test() {
+            assert_eq!(GameConfig::new(5, 4).observer().size(), 20);
+
+            let config = GameConfig {
+                observation: Encoding::Rays(RayVision::new(vec![(0, 1), (1, 0)])),
+                ..GameConfig::new(5, 4)
+            };
+            assert_eq!(
+                config.observer().size(),
+                RayVision::new(vec![(0, 1), (1, 0)]).size()
+            );
+        }
+    }
+
+    mod advance {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let config = GameConfig::new(4, 3);
+
+            assert_eq!(config.advance(5, Direction::Right), Ok(6));
+            assert_eq!(config.advance(5, Direction::Up), Ok(1));
+            assert_eq!(
+                config.advance(4, Direction::Left),
+                Err(DeathCause::LeftRightWall)
+            );
+            assert_eq!(
+                config.advance(9, Direction::Down),
+                Err(DeathCause::TopBottomWall)
+            );
+        }
+
+        #[test]
+        fn test_wrap() {
+            let config = GameConfig {
+                boundary: Boundary::Wrap,
+                ..GameConfig::new(4, 3)
+            };
+
+            assert_eq!(config.advance(4, Direction::Left), Ok(7));
+            assert_eq!(config.advance(7, Direction::Right), Ok(4));
+            assert_eq!(config.advance(9, Direction::Down), Ok(1));
+            assert_eq!(config.advance(1, Direction::Up), Ok(9));
+        }
+    }
+
+    mod steer {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut config = GameConfig::new(5, 5);
+            let heading = Some(Direction::Up);
+
+            assert_eq!(
+                config.steer(heading, Direction::Left, 2),
+                Some(Direction::Left)
+            );
+            assert_eq!(
+                config.steer(heading, Direction::Down, 2),
+                Some(Direction::Down)
+            );
+            assert_eq!(
+                config.steer(heading, Direction::Down, 1),
+                Some(Direction::Down)
+            );
+
+            config.reversal = ReversalPolicy::Ignore;
+            assert_eq!(config.steer(heading, Direction::Down, 2), None);
+
+            config.reversal = ReversalPolicy::ContinueStraight;
+            assert_eq!(
+                config.steer(heading, Direction::Down, 2),
+                Some(Direction::Up)
+            );
+            assert_eq!(
+                config.steer(None, Direction::Down, 2),
+                Some(Direction::Down)
+            );
+        }
+    }
+
+    mod validate {
+        use super::*;
+        use crate::snake::hunger::HungerRefill;
+        use crate::snake::observation::RayVision;
+
+        #[test]
+        fn test() {
+            assert_eq!(GameConfig::new(5, 5).validate(), Ok(()));
+
+            let config = GameConfig {
+                walls: BTreeSet::from([3, 25]),
+                ..GameConfig::new(5, 5)
+            };
+            assert_eq!(
+                config.validate(),
+                Err("walls include cell 25, off the board".to_string())
+            );
+
+            let invalid = [
+                GameConfig {
+                    start_length: 0,
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    walls: BTreeSet::from([12]),
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    spawn: Some(-1),
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    food_rules: FoodRules {
+                        normal_weight: 0.0,
+                        ..FoodRules::default()
+                    },
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    food_rules: FoodRules {
+                        golden_weight: f32::INFINITY,
+                        ..FoodRules::default()
+                    },
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    width: isize::MAX,
+                    height: 2,
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    food_rules: FoodRules {
+                        timed_weight: 1.0,
+                        timed_ticks: 0,
+                        ..FoodRules::default()
+                    },
+                    ..GameConfig::new(5, 5)
+                },
+                GameConfig {
+                    observation: Encoding::Rays(RayVision::new(vec![(0, 1)])),
+                    hunger: Some(Hunger::new(5, HungerRefill::Reset)),
+                    width: 0,
+                    ..GameConfig::new(5, 5)
+                },
+            ];
+            for config in invalid {
+                assert!(config.validate().is_err());
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
-#[derive(PartialEq)]
-pub(crate) enum Direction {
+use serde::{Deserialize, Serialize};
+
+/// Absolute direction the snake can move in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
     Up,
     Down,
     Left,
@@ -7,7 +11,16 @@ pub(crate) enum Direction {
 }
 
 impl Direction {
-    pub(crate) fn value(&self) -> (isize, isize) {
+    /// Every direction, in the order actions are numbered.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Offset of a single move as `(x, y)`, with `y` growing downwards.
+    pub fn value(&self) -> (isize, isize) {
         match *self {
             Direction::Up => (0, -1),
             Direction::Down => (0, 1),
@@ -15,6 +28,80 @@ impl Direction {
             Direction::Right => (1, 0),
         }
     }
+
+    /// Direction pointing the other way.
+    pub fn opposite(&self) -> Direction {
+        match *self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Direction after making `action` while facing this way.
+    pub fn turn(&self, action: RelativeAction) -> Direction {
+        match (action, *self) {
+            (RelativeAction::Straight, direction) => direction,
+            (RelativeAction::TurnLeft, Direction::Up) => Direction::Left,
+            (RelativeAction::TurnLeft, Direction::Left) => Direction::Down,
+            (RelativeAction::TurnLeft, Direction::Down) => Direction::Right,
+            (RelativeAction::TurnLeft, Direction::Right) => Direction::Up,
+            (RelativeAction::TurnRight, direction) => {
+                direction.opposite().turn(RelativeAction::TurnLeft)
+            }
+        }
+    }
+}
+
+/// Move the snake can make relative to the direction it is facing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelativeAction {
+    TurnLeft,
+    Straight,
+    TurnRight,
+}
+
+impl RelativeAction {
+    /// Every relative action, in the order actions are numbered.
+    pub const ALL: [RelativeAction; 3] = [
+        RelativeAction::TurnLeft,
+        RelativeAction::Straight,
+        RelativeAction::TurnRight,
+    ];
+}
+
+/// Which kind of move numbered actions stand for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionSpace {
+    /// Actions are absolute directions, numbered as in [`Direction::ALL`].
+    #[default]
+    Absolute,
+    /// Actions are turns relative to the heading, numbered as in
+    /// [`RelativeAction::ALL`], so the snake can never reverse.
+    Relative,
+}
+
+impl ActionSpace {
+    /// Number of actions to choose from.
+    pub fn size(&self) -> usize {
+        match self {
+            ActionSpace::Absolute => Direction::ALL.len(),
+            ActionSpace::Relative => RelativeAction::ALL.len(),
+        }
+    }
+
+    /// Direction that action number `action` moves in when facing `heading`.
+    /// A snake with no heading is treated as facing up.
+    pub fn direction(&self, action: usize, heading: Option<Direction>) -> Direction {
+        match self {
+            ActionSpace::Absolute => Direction::ALL[action],
+            ActionSpace::Relative => heading
+                .unwrap_or(Direction::Up)
+                .turn(RelativeAction::ALL[action]),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -28,4 +115,62 @@ mod tests {
         assert_eq!(Direction::Left.value(), (-1, 0));
         assert_eq!(Direction::Right.value(), (1, 0));
     }
+
+    #[test]
+    fn test_opposite() {
+        assert_eq!(Direction::Up.opposite(), Direction::Down);
+        assert_eq!(Direction::Down.opposite(), Direction::Up);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::Right.opposite(), Direction::Left);
+    }
+
+    #[test]
+    fn test_turn() {
+        assert_eq!(
+            Direction::Up.turn(RelativeAction::TurnLeft),
+            Direction::Left
+        );
+        assert_eq!(
+            Direction::Up.turn(RelativeAction::TurnRight),
+            Direction::Right
+        );
+        assert_eq!(
+            Direction::Left.turn(RelativeAction::TurnLeft),
+            Direction::Down
+        );
+        assert_eq!(
+            Direction::Left.turn(RelativeAction::TurnRight),
+            Direction::Up
+        );
+        assert_eq!(
+            Direction::Down.turn(RelativeAction::TurnRight),
+            Direction::Left
+        );
+        assert_eq!(
+            Direction::Right.turn(RelativeAction::TurnRight),
+            Direction::Down
+        );
+
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn(RelativeAction::Straight), direction);
+            for action in RelativeAction::ALL {
+                assert_ne!(direction.turn(action), direction.opposite());
+            }
+        }
+    }
+
+    #[test]
+    fn test_action_space() {
+        assert_eq!(ActionSpace::Absolute.size(), 4);
+        assert_eq!(ActionSpace::Relative.size(), 3);
+        assert_eq!(
+            ActionSpace::Absolute.direction(2, Some(Direction::Right)),
+            Direction::Left
+        );
+        assert_eq!(
+            ActionSpace::Relative.direction(0, Some(Direction::Right)),
+            Direction::Up
+        );
+        assert_eq!(ActionSpace::Relative.direction(2, None), Direction::Right);
+    }
 }
@@ -0,0 +1,210 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::snake::game::{Game, StepOutcome};
+
+/// Shape of the actions an [`Environment`] takes or the observations it
+/// gives.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Space {
+    /// One of `n` choices, numbered from 0.
+    Discrete(usize),
+    /// A vector of `len` values, each between `low` and `high`.
+    Box { len: usize, low: f32, high: f32 },
+}
+
+impl Space {
+    /// Number of choices for a discrete space, or values for a box space.
+    pub fn size(&self) -> usize {
+        match *self {
+            Space::Discrete(n) => n,
+            Space::Box { len, .. } => len,
+        }
+    }
+}
+
+/// Something an agent can be trained against by repeatedly observing it and
+/// acting on it.
+pub trait Environment {
+    /// What the agent chooses on every step.
+    type Action;
+    /// What the agent sees before every step.
+    type Observation;
+    /// Extra details about a step, beyond its reward.
+    type Info;
+
+    /// Starts a new episode from `seed` and returns its first observation.
+    ///
+    /// Episodes started from the same seed play out identically.
+    fn reset(&mut self, seed: u64) -> Self::Observation;
+
+    /// Takes `action` and returns the observation after it, the reward for
+    /// it, whether the episode is over and any extra details.
+    fn step(&mut self, action: Self::Action) -> (Self::Observation, f32, bool, Self::Info);
+
+    /// Actions the environment accepts.
+    fn action_space(&self) -> Space;
+
+    /// Observations the environment gives.
+    fn observation_space(&self) -> Space;
+}
+
+/// A game driven one move at a time, observed through the encoding in its
+/// config.
+///
+/// Actions are numbered following the action space in its config, and the
+/// reward for a move is its change in score.
+impl Environment for Game {
+    type Action = usize;
+    type Observation = Vec<f32>;
+    type Info = StepOutcome;
+
+    fn reset(&mut self, seed: u64) -> Vec<f32> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        *self = Game::from_config(&mut rng, self.config().clone());
+
+        self.observe()
+    }
+
+    fn step(&mut self, action: usize) -> (Vec<f32>, f32, bool, StepOutcome) {
+        let direction = self.config().actions.direction(action, self.heading());
+        let outcome = self.move_snake(direction);
+
+        (
+            self.observe(),
+            outcome.score_delta as f32,
+            outcome.finished,
+            outcome,
+        )
+    }
+
+    fn action_space(&self) -> Space {
+        Space::Discrete(self.config().actions.size())
+    }
+
+    fn observation_space(&self) -> Space {
+        let observer = self.observer();
+        let (low, high) = observer.bounds();
+
+        Space::Box {
+            len: observer.size(),
+            low,
+            high,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::config::GameConfig;
+    use crate::snake::direction::{ActionSpace, Direction};
+    use crate::snake::fixtures::game_with;
+    use crate::snake::game::DeathCause;
+
+    mod reset {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut game = game_with(GameConfig::new(5, 5));
+            game.step(0);
+            game.step(0);
+
+            let observation = game.reset(7);
+
+            assert_eq!(game.head(), 12);
+            assert_eq!(game.score(), 0);
+            assert!(!game.finished());
+            assert_eq!(observation, game.observe());
+        }
+
+        #[test]
+        fn test_seed() {
+            let mut a = game_with(GameConfig::new(5, 5));
+            let mut b = game_with(GameConfig::new(5, 5));
+
+            assert_eq!(a.reset(3), b.reset(3));
+            assert_eq!(a.food(), b.food());
+
+            let foods: Vec<isize> = (0..8)
+                .map(|seed| {
+                    a.reset(seed);
+                    a.food()[0].cell
+                })
+                .collect();
+            assert!(foods.iter().any(|&cell| cell != foods[0]));
+        }
+    }
+
+    mod step {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut game = game_with(GameConfig::new(5, 5));
+            game.reset(0);
+
+            let (observation, reward, done, outcome) = game.step(2);
+
+            assert_eq!(game.head(), 11);
+            assert_eq!(game.heading(), Some(Direction::Left));
+            assert_eq!(observation, game.observe());
+            assert_eq!(reward, outcome.score_delta as f32);
+            assert!(!done);
+        }
+
+        #[test]
+        fn test_done() {
+            let mut game = game_with(GameConfig::new(5, 5));
+            game.reset(0);
+
+            game.step(3);
+            game.step(3);
+            let (_, reward, done, outcome) = game.step(3);
+
+            assert!(done);
+            assert_eq!(reward, 0.0);
+            assert_eq!(outcome.death_cause, Some(DeathCause::LeftRightWall));
+        }
+
+        #[test]
+        fn test_relative() {
+            let mut game = game_with(GameConfig {
+                actions: ActionSpace::Relative,
+                ..GameConfig::new(5, 5)
+            });
+            game.reset(0);
+
+            // With no heading the snake counts as facing up
+            game.step(1);
+            assert_eq!(game.heading(), Some(Direction::Up));
+            game.step(2);
+            assert_eq!(game.heading(), Some(Direction::Right));
+            game.step(0);
+            assert_eq!(game.heading(), Some(Direction::Up));
+            assert_eq!(game.action_space(), Space::Discrete(3));
+        }
+    }
+
+    mod spaces {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let game = game_with(GameConfig::new(5, 5));
+
+            assert_eq!(game.action_space(), Space::Discrete(4));
+            assert_eq!(game.action_space().size(), 4);
+            assert_eq!(
+                game.observation_space(),
+                Space::Box {
+                    len: 25,
+                    low: -1.0,
+                    high: 1.0
+                }
+            );
+            assert_eq!(game.observe().len(), game.observation_space().size());
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::snake::train::TrainConfig;
+
+/// A training run described by a TOML file, naming the directory its
+/// results are written to.
+///
+/// ```toml
+/// output = "runs/baseline"
+///
+/// [train]
+/// population = 200
+/// hidden = [16, 8]
+/// selection = { tournament = 3 }
+///
+/// [train.game]
+/// width = 12
+/// hunger = { limit = 100, refill = "reset" }
+/// observation = "tiles"
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Experiment {
+    /// Directory the results of the run are written to.
+    pub output: PathBuf,
+    /// Parameters of the run.
+    #[serde(default)]
+    pub train: TrainConfig,
+}
+
+#[derive(Debug)]
+pub enum ExperimentError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    /// The file parsed, but describes a run that cannot be started.
+    Invalid(String),
+}
+
+impl Experiment {
+    /// Reads, parses and checks the experiment file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Experiment, ExperimentError> {
+        std::fs::read_to_string(path)
+            .map_err(ExperimentError::Io)?
+            .parse()
+    }
+
+    /// The experiment as TOML, with every parameter spelled out so the run
+    /// can be reproduced even if the defaults change.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("experiments are always representable as TOML")
+    }
+}
+
+impl FromStr for Experiment {
+    type Err = ExperimentError;
+
+    fn from_str(s: &str) -> Result<Experiment, ExperimentError> {
+        let experiment: Experiment = toml::from_str(s).map_err(ExperimentError::Parse)?;
+
+        experiment
+            .train
+            .validate()
+            .map_err(|err| ExperimentError::Invalid(format!("train.{}", err)))?;
+
+        Ok(experiment)
+    }
+}
+
+impl fmt::Display for ExperimentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExperimentError::Io(err) => write!(f, "could not read experiment: {}", err),
+            ExperimentError::Parse(err) => write!(f, "malformed experiment: {}", err),
+            ExperimentError::Invalid(reason) => write!(f, "invalid experiment: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ExperimentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExperimentError::Io(err) => Some(err),
+            ExperimentError::Parse(err) => Some(err),
+            ExperimentError::Invalid(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::config::GameConfig;
+    use crate::snake::game::Boundary;
+    use crate::snake::hunger::{Hunger, HungerRefill};
+    use crate::snake::observation::{BodyEncoding, Encoding};
+    use crate::snake::train::{Fitness, Selection};
+    use lib_neural_network::Activation;
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let experiment: Experiment = r#"
+                output = "runs/test"
+
+                [train]
+                population = 20
+                hidden = [8, 4]
+                activation = "tanh"
+                selection = { tournament = 3 }
+                fitness = { score_and_survival = 0.5 }
+
+                [train.game]
+                width = 6
+                boundary = "wrap"
+                hunger = { limit = 50, refill = { add = 10 } }
+                observation = { grid = { body = "gradient", crop = 2 } }
+            "#
+            .parse()
+            .unwrap();
+
+            let train = &experiment.train;
+            assert_eq!(experiment.output, PathBuf::from("runs/test"));
+            assert_eq!(train.population, 20);
+            assert_eq!(train.hidden, vec![8, 4]);
+            assert_eq!(train.activation, Activation::Tanh);
+            assert_eq!(train.selection, Selection::Tournament(3));
+            assert_eq!(train.fitness, Fitness::ScoreAndSurvival(0.5));
+            assert_eq!(train.generations, TrainConfig::default().generations);
+
+            // Rules left out keep the training defaults
+            let defaults = TrainConfig::default().game;
+            assert_eq!((train.game.width, train.game.height), (6, 10));
+            assert_eq!(train.game.actions, defaults.actions);
+            assert_eq!(train.game.boundary, Boundary::Wrap);
+            assert_eq!(
+                train.game.hunger,
+                Some(Hunger::new(50, HungerRefill::Add(10)))
+            );
+            assert_eq!(
+                train.game.observation,
+                Encoding::Grid {
+                    body: BodyEncoding::Gradient,
+                    crop: Some(2)
+                }
+            );
+            assert_eq!(train.game.start_length, 2);
+        }
+
+        #[test]
+        fn test_partial_game() {
+            let experiment: Experiment = "output = \"a\"\n[train.game]\nwidth = 12\n"
+                .parse()
+                .unwrap();
+
+            let expected = GameConfig {
+                width: 12,
+                ..TrainConfig::default().game
+            };
+            assert_eq!(experiment.train.game, expected);
+
+            // Leaving the table out keeps every default, as does a [train]
+            // table without one
+            let experiment: Experiment =
+                "output = \"a\"\n[train]\npopulation = 5\n".parse().unwrap();
+            assert_eq!(experiment.train.game, TrainConfig::default().game);
+
+            assert!(matches!(
+                "output = \"a\"\n[train.game]\nwidht = 12\n".parse::<Experiment>(),
+                Err(ExperimentError::Parse(_))
+            ));
+        }
+
+        #[test]
+        fn test_defaults() {
+            let experiment: Experiment = "output = \"runs/test\"".parse().unwrap();
+
+            assert_eq!(experiment.train, TrainConfig::default());
+        }
+
+        #[test]
+        fn test_errors() {
+            assert!(matches!(
+                "".parse::<Experiment>(),
+                Err(ExperimentError::Parse(_))
+            ));
+            assert!(matches!(
+                "output = \"a\"\n[train]\npopulaton = 5\n".parse::<Experiment>(),
+                Err(ExperimentError::Parse(_))
+            ));
+            assert!(matches!(
+                "output = \"a\"\n[train]\nselection = \"best\"\n".parse::<Experiment>(),
+                Err(ExperimentError::Parse(_))
+            ));
+
+            // Values JSON has no room for still reach the checks
+            let err = "output = \"a\"\n[train.game]\nfood_rules = { golden_weight = inf }\n"
+                .parse::<Experiment>()
+                .unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("food_rules weights must be finite"));
+
+            let err = "output = \"a\"\n[train.game]\nwalls = [200]\n"
+                .parse::<Experiment>()
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "invalid experiment: train.game.walls include cell 200, off the board"
+            );
+        }
+    }
+
+    mod to_toml {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut experiment: Experiment = "output = \"runs/test\"".parse().unwrap();
+            experiment.train.game.walls.insert(3);
+            experiment.train.game.spawn = Some(5);
+
+            let resolved: Experiment = experiment.to_toml().parse().unwrap();
+            assert_eq!(resolved, experiment);
+        }
+
+        #[test]
+        fn test_hunger_off() {
+            let mut experiment: Experiment = "output = \"runs/test\"".parse().unwrap();
+            experiment.train.game.hunger = None;
+
+            let toml = experiment.to_toml();
+            assert!(toml.contains("hunger = \"off\""));
+
+            let resolved: Experiment = toml.parse().unwrap();
+            assert_eq!(resolved.train.game.hunger, None);
+            assert_eq!(resolved, experiment);
+
+            assert!(matches!(
+                "output = \"a\"\n[train.game]\nhunger = \"of\"\n".parse::<Experiment>(),
+                Err(ExperimentError::Parse(_))
+            ));
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/experiments/baseline.toml");
+            let experiment = Experiment::load(path).unwrap();
+
+            assert_eq!(experiment.output, PathBuf::from("runs/baseline"));
+            assert_eq!(experiment.train, TrainConfig::default());
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::snake::game::{DeathCause, Game, StepOutcome};
+
+/// Record of every move made in a game, kept alongside it while it is
+/// played.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trajectory {
+    outcomes: Vec<StepOutcome>,
+    food_distances: Vec<Option<isize>>,
+}
+
+impl Trajectory {
+    /// Starts a trajectory for `game` before its first move.
+    pub fn new(game: &Game) -> Trajectory {
+        Trajectory {
+            outcomes: Vec::new(),
+            food_distances: vec![nearest_food(game)],
+        }
+    }
+
+    /// Records a move that had `outcome` and left the game as `game`.
+    pub fn record(&mut self, game: &Game, outcome: StepOutcome) {
+        self.outcomes.push(outcome);
+        self.food_distances.push(nearest_food(game));
+    }
+
+    /// Number of moves made.
+    pub fn steps(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// What happened on every move.
+    pub fn outcomes(&self) -> &[StepOutcome] {
+        &self.outcomes
+    }
+
+    /// Distance from the head to the nearest food before the first move and
+    /// after every move, or `None` if there was no food.
+    pub fn food_distances(&self) -> &[Option<isize>] {
+        &self.food_distances
+    }
+}
+
+// Number of moves from the head to the nearest food on an empty board
+fn nearest_food(game: &Game) -> Option<isize> {
+    let head = game.head();
+
+    game.food()
+        .iter()
+        .map(|food| game.distance(head, food.cell))
+        .min()
+}
+
+/// Scores how well an agent played a finished game.
+pub trait FitnessFunction {
+    /// Fitness of the agent that played `game`, moving as in `trajectory`.
+    fn fitness(&self, game: &Game, trajectory: &Trajectory) -> f32;
+}
+
+/// Rewards an agent for a single move.
+pub trait RewardShaper {
+    /// Reward for move `step` of `trajectory`, counting from 0.
+    fn reward(&self, trajectory: &Trajectory, step: usize) -> f32;
+}
+
+/// Fitness equal to the final score.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreOnly;
+
+impl FitnessFunction for ScoreOnly {
+    fn fitness(&self, game: &Game, _trajectory: &Trajectory) -> f32 {
+        game.score() as f32
+    }
+}
+
+/// Fitness equal to the final score plus `survival` for every move made.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreAndSurvival {
+    pub survival: f32,
+}
+
+impl FitnessFunction for ScoreAndSurvival {
+    fn fitness(&self, game: &Game, trajectory: &Trajectory) -> f32 {
+        game.score() as f32 + self.survival * trajectory.steps() as f32
+    }
+}
+
+/// The usual fitness for evolved snakes,
+/// `steps + 2^score + 500 * score^2.1 - score^1.2 * (steps / 4)^1.3`.
+///
+/// Eating is worth far more than surviving, and the last term punishes
+/// snakes that wander about for a long time between meals.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Classic;
+
+impl FitnessFunction for Classic {
+    fn fitness(&self, game: &Game, trajectory: &Trajectory) -> f32 {
+        let steps = trajectory.steps() as f64;
+        let score = game.score() as f64;
+
+        let fitness = steps + 2f64.powf(score) + 500.0 * score.powf(2.1)
+            - score.powf(1.2) * (0.25 * steps).powf(1.3);
+
+        fitness as f32
+    }
+}
+
+/// Fitness equal to the total reward given by a shaper over every move.
+pub struct TotalReward(pub Box<dyn RewardShaper>);
+
+impl FitnessFunction for TotalReward {
+    fn fitness(&self, _game: &Game, trajectory: &Trajectory) -> f32 {
+        (0..trajectory.steps())
+            .map(|step| self.0.reward(trajectory, step))
+            .sum()
+    }
+}
+
+/// Takes `penalty` off another fitness when the snake died, rather than
+/// filling the board.
+pub struct DeathPenalty {
+    pub fitness: Box<dyn FitnessFunction>,
+    pub penalty: f32,
+}
+
+impl FitnessFunction for DeathPenalty {
+    fn fitness(&self, game: &Game, trajectory: &Trajectory) -> f32 {
+        let fitness = self.fitness.fitness(game, trajectory);
+
+        match game.death_cause() {
+            Some(DeathCause::BoardFull) | None => fitness,
+            Some(_) => fitness - self.penalty,
+        }
+    }
+}
+
+/// Rewards each move with its change in score.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoreReward;
+
+impl RewardShaper for ScoreReward {
+    fn reward(&self, trajectory: &Trajectory, step: usize) -> f32 {
+        trajectory.outcomes()[step].score_delta as f32
+    }
+}
+
+/// Rewards each move with its change in score, plus `approach` for moving
+/// towards the nearest food and minus `retreat` for moving away from it.
+///
+/// Moves that eat or end the game are rewarded with the change in score
+/// alone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistanceShaping {
+    pub approach: f32,
+    pub retreat: f32,
+}
+
+impl RewardShaper for DistanceShaping {
+    fn reward(&self, trajectory: &Trajectory, step: usize) -> f32 {
+        let outcome = trajectory.outcomes()[step];
+        let distances = trajectory.food_distances();
+        let reward = outcome.score_delta as f32;
+
+        if outcome.ate_food || outcome.finished {
+            return reward;
+        }
+
+        match (distances[step], distances[step + 1]) {
+            (Some(before), Some(after)) if after < before => reward + self.approach,
+            (Some(before), Some(after)) if after > before => reward - self.retreat,
+            _ => reward,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::config::GameConfig;
+    use crate::snake::direction::Direction;
+    use crate::snake::fixtures::{food, game_with};
+
+    // Game on a 10x10 board with the snake on 44 and food on 41
+    fn new_game() -> Game {
+        let mut game = game_with(GameConfig::new(10, 10));
+        game.set_food(food(&[41]));
+
+        game
+    }
+
+    fn play(game: &mut Game, trajectory: &mut Trajectory, directions: &[Direction]) {
+        for &direction in directions {
+            let outcome = game.move_snake(direction);
+            trajectory.record(game, outcome);
+        }
+    }
+
+    mod trajectory {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut game = new_game();
+            let mut trajectory = Trajectory::new(&game);
+            play(
+                &mut game,
+                &mut trajectory,
+                &[Direction::Left, Direction::Up],
+            );
+
+            assert_eq!(trajectory.steps(), 2);
+            assert_eq!(trajectory.food_distances(), &[Some(3), Some(2), Some(3)]);
+            assert!(!trajectory.outcomes()[1].finished);
+        }
+    }
+
+    mod fitness_function {
+        use super::*;
+
+        // Eats the food, then runs up into the top wall
+        fn finished_game() -> (Game, Trajectory) {
+            let mut game = new_game();
+            let mut trajectory = Trajectory::new(&game);
+            play(&mut game, &mut trajectory, &[Direction::Left; 3]);
+            game.set_food(food(&[99]));
+            play(&mut game, &mut trajectory, &[Direction::Up; 5]);
+            assert_eq!(game.death_cause(), Some(DeathCause::TopBottomWall));
+
+            (game, trajectory)
+        }
+
+        #[test]
+        fn test_score_only() {
+            let (game, trajectory) = finished_game();
+
+            assert_eq!(ScoreOnly.fitness(&game, &trajectory), 1.0);
+        }
+
+        #[test]
+        fn test_score_and_survival() {
+            let (game, trajectory) = finished_game();
+            let fitness = ScoreAndSurvival { survival: 0.5 };
+
+            assert_eq!(fitness.fitness(&game, &trajectory), 5.0);
+        }
+
+        #[test]
+        fn test_classic() {
+            let (game, trajectory) = finished_game();
+            let expected = 8.0 + 2.0 + 500.0 - 2f64.powf(1.3);
+
+            assert_eq!(Classic.fitness(&game, &trajectory), expected as f32);
+        }
+
+        #[test]
+        fn test_death_penalty() {
+            let (game, trajectory) = finished_game();
+            let fitness = DeathPenalty {
+                fitness: Box::new(ScoreOnly),
+                penalty: 10.0,
+            };
+
+            assert_eq!(fitness.fitness(&game, &trajectory), -9.0);
+
+            let mut game = new_game();
+            let mut trajectory = Trajectory::new(&game);
+            play(&mut game, &mut trajectory, &[Direction::Left]);
+            assert_eq!(fitness.fitness(&game, &trajectory), 0.0);
+        }
+
+        #[test]
+        fn test_total_reward() {
+            let (game, trajectory) = finished_game();
+
+            assert_eq!(
+                TotalReward(Box::new(ScoreReward)).fitness(&game, &trajectory),
+                1.0
+            );
+        }
+    }
+
+    mod reward_shaper {
+        use super::*;
+
+        #[test]
+        fn test_distance_shaping() {
+            let mut game = new_game();
+            let mut trajectory = Trajectory::new(&game);
+            play(
+                &mut game,
+                &mut trajectory,
+                &[Direction::Left, Direction::Up],
+            );
+            let shaping = DistanceShaping {
+                approach: 0.1,
+                retreat: 0.2,
+            };
+
+            assert_eq!(shaping.reward(&trajectory, 0), 0.1);
+            assert_eq!(shaping.reward(&trajectory, 1), -0.2);
+        }
+
+        #[test]
+        fn test_distance_shaping_eat() {
+            let mut game = new_game();
+            let mut trajectory = Trajectory::new(&game);
+            play(&mut game, &mut trajectory, &[Direction::Left; 3]);
+            let shaping = DistanceShaping {
+                approach: 0.1,
+                retreat: 0.2,
+            };
+
+            assert_eq!(shaping.reward(&trajectory, 2), 1.0);
+            let total = TotalReward(Box::new(shaping)).fitness(&game, &trajectory);
+            assert!((total - 1.2).abs() < 1e-6);
+        }
+    }
+}
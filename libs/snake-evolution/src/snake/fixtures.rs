@@ -0,0 +1,27 @@
+// Setups shared by the test modules
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::snake::config::GameConfig;
+use crate::snake::food::{Food, FoodKind};
+use crate::snake::game::Game;
+
+// Game following `config`, seeded the same way every time
+pub(crate) fn game_with(config: GameConfig) -> Game {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    Game::from_config(&mut rng, config)
+}
+
+// Normal food on each of `cells`
+pub(crate) fn food(cells: &[isize]) -> Vec<Food> {
+    cells
+        .iter()
+        .map(|&cell| Food {
+            cell,
+            kind: FoodKind::Normal,
+            ticks_left: None,
+        })
+        .collect()
+}
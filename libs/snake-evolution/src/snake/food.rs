@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// Where new food is placed on the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoodSpawn {
+    /// Uniformly at random among the free cells.
+    #[default]
+    Uniform,
+    /// Uniformly at random among the free cells in the half furthest from
+    /// the head of the snake.
+    FarFromHead,
+    /// Along a fixed sequence of cells drawn from `seed`, skipping cells that
+    /// are taken. Games sharing a seed see food in the same places whatever
+    /// the game's own generator.
+    Sequence { seed: u64 },
+}
+
+/// Kind of a piece of food, deciding what happens when it is eaten.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FoodKind {
+    /// Scores a point and grows the snake.
+    Normal,
+    /// Scores bonus points and grows the snake.
+    Golden,
+    /// Shrinks the snake and costs points, without feeding it.
+    Poison,
+    /// Like normal food, but disappears if not eaten in time.
+    Timed,
+}
+
+impl FoodKind {
+    /// Character used to draw this kind of food.
+    pub fn glyph(&self) -> char {
+        match *self {
+            FoodKind::Normal => '\u{2022}',
+            FoodKind::Golden => '\u{2605}',
+            FoodKind::Poison => '\u{00D7}',
+            FoodKind::Timed => '\u{25CB}',
+        }
+    }
+}
+
+/// A piece of food on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Food {
+    /// Cell the food is on.
+    pub cell: isize,
+    pub kind: FoodKind,
+    /// Moves left before timed food disappears, `None` for other kinds.
+    pub ticks_left: Option<usize>,
+}
+
+/// How often each kind of food spawns and what it does when eaten.
+///
+/// Spawn weights are relative to each other, so only normal food spawns by
+/// default.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FoodRules {
+    pub normal_weight: f32,
+    pub golden_weight: f32,
+    pub poison_weight: f32,
+    pub timed_weight: f32,
+    /// Points scored for golden food.
+    pub golden_score: usize,
+    /// Cells poison takes off the tail. The head always remains.
+    pub poison_shrink: usize,
+    /// Points poison costs. The score never drops below zero.
+    pub poison_penalty: usize,
+    /// Moves timed food stays on the board.
+    pub timed_ticks: usize,
+}
+
+impl FoodRules {
+    pub(crate) fn weights(&self) -> [(FoodKind, f32); 4] {
+        [
+            (FoodKind::Normal, self.normal_weight),
+            (FoodKind::Golden, self.golden_weight),
+            (FoodKind::Poison, self.poison_weight),
+            (FoodKind::Timed, self.timed_weight),
+        ]
+    }
+}
+
+impl Default for FoodRules {
+    fn default() -> Self {
+        FoodRules {
+            normal_weight: 1.0,
+            golden_weight: 0.0,
+            poison_weight: 0.0,
+            timed_weight: 0.0,
+            golden_score: 5,
+            poison_shrink: 1,
+            poison_penalty: 1,
+            timed_ticks: 20,
+        }
+    }
+}
@@ -1,165 +1,853 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
 
-use crate::snake::direction::Direction;
+use serde::{Deserialize, Serialize};
 
-const MIN_SNAKE_LENGTH: usize = 2;
+use crate::snake::board::{Board, Body};
+use crate::snake::config::GameConfig;
+use crate::snake::direction::{Direction, RelativeAction};
+use crate::snake::food::{Food, FoodKind};
+use crate::snake::level::Level;
+use crate::snake::observation::Observer;
 
-pub(crate) struct Game {
-    size: isize,
-    snake: VecDeque<isize>,
-    food: isize,
+/// Reason a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// The snake moved off the left or right edge of the board.
+    LeftRightWall,
+    /// The snake moved off the top or bottom edge of the board.
+    TopBottomWall,
+    /// The snake moved into its own body.
+    SelfCollision,
+    /// The snake moved into a wall inside the board.
+    Obstacle,
+    /// The snake ran out of moves without eating.
+    Starvation,
+    /// The snake filled the board, leaving nowhere to place food.
+    BoardFull,
+    /// The snake moved onto the same cell as the head of another snake.
+    HeadToHead,
+    /// The snake moved into the body of another snake.
+    SnakeCollision,
+}
+
+impl fmt::Display for DeathCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            DeathCause::LeftRightWall => "hit the left or right wall",
+            DeathCause::TopBottomWall => "hit the top or bottom wall",
+            DeathCause::SelfCollision => "ran into itself",
+            DeathCause::Obstacle => "hit an obstacle",
+            DeathCause::Starvation => "starved",
+            DeathCause::BoardFull => "filled the board",
+            DeathCause::HeadToHead => "collided head-on with another snake",
+            DeathCause::SnakeCollision => "ran into another snake",
+        };
+
+        f.write_str(description)
+    }
+}
+
+/// What happens when the snake moves off an edge of the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Boundary {
+    /// The edges are walls and end the game.
+    #[default]
+    Walls,
+    /// The snake re-enters on the opposite edge.
+    Wrap,
+}
+
+/// What happens when the snake is told to move back the way it came.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReversalPolicy {
+    /// The snake turns into its own neck and dies.
+    #[default]
+    Die,
+    /// The move is skipped and the snake stays where it is.
+    Ignore,
+    /// The snake keeps moving in its current heading.
+    ContinueStraight,
+}
+
+/// Result of advancing the game by a single move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StepOutcome {
+    /// Whether the snake ate a piece of food on this move.
+    pub ate_food: bool,
+    /// Kind of food eaten on this move.
+    pub eaten: Option<FoodKind>,
+    /// Number of timed food items that disappeared on this move.
+    pub expired: usize,
+    /// Whether the game is over after this move.
+    pub finished: bool,
+    /// Why the game ended, set only on the move that ended it.
+    pub death_cause: Option<DeathCause>,
+    /// Change in score caused by this move.
+    pub score_delta: isize,
+}
+
+/// Contents of a single cell on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    /// Nothing is on the cell.
+    Empty,
+    /// A wall inside the board.
+    Wall,
+    /// Part of the snake.
+    Snake,
+    /// A piece of food.
+    Food,
+}
+
+/// State of a single game of snake on a rectangular board.
+///
+/// Cells are addressed by their index `y * width + x`, starting from the top
+/// left corner of the board.
+///
+/// Alongside the snake and food the game keeps a grid with the tile on every
+/// cell and a list of the empty cells, so collisions, food placement and
+/// rendering don't need to search the snake.
+pub struct Game {
+    config: GameConfig,
+    board: Board,
+    // Built once, as the game may be observed after every move
+    observer: Box<dyn Observer>,
+    snake: Body,
+    heading: Option<Direction>,
+    food: Vec<Food>,
     score: usize,
     finished: bool,
+    death_cause: Option<DeathCause>,
 }
 
 impl Game {
-    pub(crate) fn new(mut size: isize) -> Game {
-        if size < 3 {
-            size = 3
-        }
+    /// Creates a new game with classic rules on a `width` by `height` board,
+    /// with the snake placed in the centre. Both dimensions are enlarged to
+    /// at least 3.
+    ///
+    /// `rng` seeds the game's own generator, which places every piece of
+    /// food, so games created from identically seeded generators play out
+    /// identically.
+    pub fn new(rng: &mut dyn rand::RngCore, width: isize, height: isize) -> Game {
+        Game::from_config(rng, GameConfig::new(width, height))
+    }
 
-        // Calculate center square for initial snake placement
-        let center = if size % 2 == 0 {
-            size / 2
-        } else {
-            size / 2 + 1
-        };
-        let center_square = size * (center - 1) + center - 1;
+    /// Creates a new game with classic rules on the board described by
+    /// `level`, with the snake placed on the level's start cell.
+    pub fn from_level(rng: &mut dyn rand::RngCore, level: &Level) -> Game {
+        Game::from_config(rng, GameConfig::from_level(level))
+    }
 
-        let mut snake = VecDeque::with_capacity(size.pow(2).try_into().unwrap());
-        snake.push_back(center_square);
+    /// Creates a new game following the rules and starting conditions in
+    /// `config`.
+    ///
+    /// A board with no free cell left for food once the snake is laid out
+    /// starts out finished, as if the snake had filled it.
+    pub fn from_config(rng: &mut dyn rand::RngCore, config: GameConfig) -> Game {
+        let area = config.width * config.height;
+        let spawn = config.spawn_cell();
 
-        let snake_set: HashSet<isize> = snake.iter().cloned().collect();
-        let board_set: HashSet<isize> = HashSet::from_iter((0..size.pow(2)).collect::<Vec<_>>());
-        let valid_squares = &board_set - &snake_set;
-        let food = *valid_squares.iter().next().unwrap();
+        assert!(config.width > 0 && config.height > 0);
+        assert!(config.start_length > 0);
+        assert!(config.food_count > 0);
+        assert!(config
+            .food_rules
+            .weights()
+            .iter()
+            .all(|(_, weight)| *weight >= 0.0));
+        assert!(config
+            .food_rules
+            .weights()
+            .iter()
+            .any(|(_, weight)| *weight > 0.0));
+        assert!((0..area).contains(&spawn) && !config.walls.contains(&spawn));
 
-        Game {
-            size,
+        let mut board = Board::new(rng, &config);
+        let snake = board.spawn(&config, spawn, config.heading);
+        let mut game = Game {
+            board,
+            observer: config.observer(),
             snake,
-            food,
+            heading: config.heading,
+            food: Vec::with_capacity(config.food_count),
             score: 0,
             finished: false,
+            death_cause: None,
+            config,
+        };
+        // Check if there is anywhere to place the food
+        if !game.board.top_up(&game.config, &mut game.food, Some(spawn)) {
+            game.game_over(DeathCause::BoardFull, StepOutcome::default());
         }
+
+        game
     }
 
-    pub(crate) fn move_snake(&mut self, direction: Direction) {
-        let (x, y) = direction.value();
-        let mut head = *self.snake.back().unwrap();
+    /// Moves the snake one cell in `direction` and advances the game.
+    ///
+    /// Moves made after the game has finished are ignored.
+    pub fn move_snake(&mut self, direction: Direction) -> StepOutcome {
+        if self.finished {
+            return StepOutcome {
+                finished: true,
+                ..Default::default()
+            };
+        }
 
-        // Check if next move would cause snake to collide with wall
-        if (head % self.size == self.size - 1 && direction == Direction::Right)
-            || (head % self.size == 0 && direction == Direction::Left)
+        // Check if the snake is trying to turn back into its neck
+        let direction = match self
+            .config
+            .steer(self.heading, direction, self.snake.cells.len())
         {
-            self.game_over()
+            Some(direction) => direction,
+            // A skipped move still counts as a turn for hunger and timed food
+            None => return self.end_turn(StepOutcome::default()),
+        };
+        self.heading = Some(direction);
+
+        // Check if the snake moved off a walled edge of the board
+        match self.config.advance(self.head(), direction) {
+            Ok(head) => self.step(head),
+            Err(cause) => self.game_over(cause, StepOutcome::default()),
         }
+    }
 
-        // Add x and y values to current head index
-        // y is multiplied by grid size to shift index by a whole row
-        head += x + y * self.size;
-        self.snake.push_back(head);
+    /// Moves the snake one cell after turning by `action` from its heading,
+    /// or from facing up if it has no heading yet, and advances the game.
+    pub fn move_relative(&mut self, action: RelativeAction) -> StepOutcome {
+        let heading = self.heading.unwrap_or(Direction::Up);
 
-        self.step();
+        self.move_snake(heading.turn(action))
     }
 
-    fn step(&mut self) {
-        let head = *self.snake.back().unwrap();
+    fn step(&mut self, head: isize) -> StepOutcome {
+        let outcome = match self.tile(head) {
+            // Check if snake moved into a wall inside the board
+            Tile::Wall => {
+                return self.game_over(DeathCause::Obstacle, StepOutcome::default());
+            }
+            // Check if snake moved into itself
+            Tile::Snake => {
+                self.snake.cells.push_back(head);
+                return self.game_over(DeathCause::SelfCollision, StepOutcome::default());
+            }
+            // Check if snake found some food
+            Tile::Food => {
+                let i = self.food.iter().position(|food| food.cell == head).unwrap();
+                let food = self.food.swap_remove(i);
+                self.eat(food.kind)
+            }
+            Tile::Empty => StepOutcome::default(),
+        };
+        self.board.move_body(&mut self.snake, head);
 
-        // Check if snake has moved off grid vertically
-        if !(0..self.size.pow(2)).contains(&head) {
-            self.game_over()
+        // Poison takes cells off the tail, but always leaves the head
+        if outcome.eaten == Some(FoodKind::Poison) {
+            let shrink = self.config.food_rules.poison_shrink;
+            self.board.shrink(&mut self.snake, shrink);
         }
 
-        // Check if snake moved into itself
-        if self.snake.iter().filter(|i| *i == &head).count() > 1 {
-            self.game_over()
-        }
+        self.end_turn(outcome)
+    }
 
-        // Check if snake found the food
-        if head == self.food {
-            self.place_food();
-        } else if self.snake.len() > MIN_SNAKE_LENGTH {
-            self.snake.pop_front();
+    fn eat(&mut self, kind: FoodKind) -> StepOutcome {
+        let rules = &self.config.food_rules;
+        let score_delta = match kind {
+            FoodKind::Normal | FoodKind::Timed => 1,
+            FoodKind::Golden => rules.golden_score as isize,
+            FoodKind::Poison => -(rules.poison_penalty.min(self.score) as isize),
+        };
+        self.score = (self.score as isize + score_delta) as usize;
+
+        if kind != FoodKind::Poison {
+            self.snake.feed(self.config.growth);
         }
-    }
 
-    fn place_food(&mut self) {
-        let snake_set: HashSet<isize> = self.snake.iter().cloned().collect();
-        let board_set: HashSet<isize> =
-            HashSet::from_iter((0..self.size.pow(2)).collect::<Vec<_>>());
-        let valid_squares = &board_set - &snake_set;
-        self.food = *valid_squares.iter().next().unwrap();
-        self.score += 1;
+        StepOutcome {
+            ate_food: true,
+            eaten: Some(kind),
+            score_delta,
+            ..Default::default()
+        }
     }
 
-    fn game_over(&mut self) {
-        self.finished = true;
-        println!("Game over!");
-    }
+    // Ages timed food and the hunger budget, and replaces any missing food
+    fn end_turn(&mut self, mut outcome: StepOutcome) -> StepOutcome {
+        let mut i = 0;
+        while i < self.food.len() {
+            let food = &mut self.food[i];
+            // Food that was already out of time goes on this move too
+            if let Some(ticks_left) = &mut food.ticks_left {
+                *ticks_left = ticks_left.saturating_sub(1);
+            }
 
-    pub(crate) fn display(&self) -> String {
-        let head = *self.snake.back().unwrap();
+            if food.ticks_left == Some(0) {
+                let cell = self.food.swap_remove(i).cell;
+                self.board.set_tile(cell, Tile::Empty);
+                outcome.expired += 1;
+            } else {
+                i += 1;
+            }
+        }
 
-        let mut out = String::from("|");
-        for _ in 0..self.size {
-            out += "---"
+        // Check if there is anywhere left to place the food
+        let head = self.head();
+        if !self.board.top_up(&self.config, &mut self.food, Some(head)) {
+            return self.game_over(DeathCause::BoardFull, outcome);
         }
 
-        let range = (0..self.size.pow(2)).step_by(self.size.try_into().unwrap());
-        for y in range {
-            out += "|\n|";
-            for x in 0..self.size {
-                let i = y + x;
-                out += match i {
-                    n if n == head => " \u{25A1} ",
-                    n if n == self.food => " \u{2022} ",
-                    n if self.snake.contains(&n) => " \u{25A0} ",
-                    _ => "   ",
-                };
-            }
+        // Check if snake has run out of moves without eating
+        let fed = matches!(outcome.eaten, Some(kind) if kind != FoodKind::Poison);
+        if !fed && self.snake.go_hungry() {
+            return self.game_over(DeathCause::Starvation, outcome);
         }
 
-        out += "|\n|";
-        for _ in 0..self.size {
-            out += "---"
+        outcome
+    }
+
+    /// Number of moves between cells `a` and `b` on an empty board, taking
+    /// the shortest way around when the board wraps.
+    pub fn distance(&self, a: isize, b: isize) -> isize {
+        self.config.distance(a, b)
+    }
+
+    fn game_over(&mut self, death_cause: DeathCause, outcome: StepOutcome) -> StepOutcome {
+        self.finished = true;
+        self.death_cause = Some(death_cause);
+
+        StepOutcome {
+            finished: true,
+            death_cause: Some(death_cause),
+            ..outcome
         }
-        out += "|\n";
+    }
 
+    /// Renders the board, snake, food and score as text.
+    pub fn display(&self) -> String {
+        let head = self.head();
+        let food: HashMap<isize, FoodKind> = self
+            .food
+            .iter()
+            .map(|food| (food.cell, food.kind))
+            .collect();
+
+        let mut out = self.board.display(|i| match self.tile(i) {
+            _ if i == head => '\u{25A1}',
+            Tile::Food => food[&i].glyph(),
+            _ => '\u{25A0}',
+        });
         out += &format!("Score: {}", self.score);
 
         out
     }
 
-    pub(crate) fn finished(&self) -> bool {
+    /// What the game looks like through the observation encoding in its
+    /// config.
+    pub fn observe(&self) -> Vec<f32> {
+        self.observer.observation(self)
+    }
+
+    // Observer for the observation encoding in the config, built once with
+    // the game
+    pub(crate) fn observer(&self) -> &dyn Observer {
+        self.observer.as_ref()
+    }
+
+    /// Tile on `cell`.
+    pub fn tile(&self, cell: isize) -> Tile {
+        self.board.tile(cell)
+    }
+
+    /// Empty cells, in no particular order.
+    pub fn free_cells(&self) -> &[isize] {
+        self.board.free_cells()
+    }
+
+    /// Cells occupied by the snake, ordered from tail to head.
+    pub fn snake(&self) -> &VecDeque<isize> {
+        &self.snake.cells
+    }
+
+    /// Cell occupied by the head of the snake.
+    pub fn head(&self) -> isize {
+        self.snake.head()
+    }
+
+    /// Direction the snake last moved in, or started facing if it has not
+    /// moved yet.
+    pub fn heading(&self) -> Option<Direction> {
+        self.heading
+    }
+
+    /// Food currently on the board.
+    pub fn food(&self) -> &[Food] {
+        &self.food
+    }
+
+    /// Points scored so far: one for each normal or timed food eaten, plus
+    /// the golden score for each golden food, less the penalty for each
+    /// poison eaten. The score never drops below zero.
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    /// Rules and starting conditions the game was created with.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// What happens when the snake moves off an edge of the board.
+    pub fn boundary(&self) -> Boundary {
+        self.config.boundary
+    }
+
+    /// Cells holding a wall inside the board.
+    pub fn walls(&self) -> &BTreeSet<isize> {
+        &self.config.walls
+    }
+
+    /// Number of columns on the board.
+    pub fn width(&self) -> isize {
+        self.config.width
+    }
+
+    /// Number of rows on the board.
+    pub fn height(&self) -> isize {
+        self.config.height
+    }
+
+    /// Whether the game has ended.
+    pub fn finished(&self) -> bool {
         self.finished
     }
+
+    /// Moves the snake can still make without eating, or `None` if the game
+    /// has no hunger limit.
+    pub fn moves_left(&self) -> Option<usize> {
+        self.snake.moves_left()
+    }
+
+    /// Why the game ended, or `None` while it is still running.
+    pub fn death_cause(&self) -> Option<DeathCause> {
+        self.death_cause
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::snake::fixtures::{food, game_with};
+    use crate::snake::food::{FoodRules, FoodSpawn};
+    use crate::snake::hunger::{Hunger, HungerRefill};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    fn food_cells(game: &Game) -> Vec<isize> {
+        game.food.iter().map(|food| food.cell).collect()
+    }
+
+    impl Game {
+        // Test setups swap in their own snake and food, so the grid has to
+        // follow along
+        pub(crate) fn set_food(&mut self, food: Vec<Food>) {
+            for old in std::mem::replace(&mut self.food, food) {
+                if self.tile(old.cell) == Tile::Food {
+                    self.board.set_tile(old.cell, Tile::Empty);
+                }
+            }
+            for i in 0..self.food.len() {
+                self.board.set_tile(self.food[i].cell, Tile::Food);
+            }
+        }
+
+        pub(crate) fn set_snake(&mut self, snake: VecDeque<isize>) {
+            for old in std::mem::replace(&mut self.snake.cells, snake) {
+                if self.tile(old) == Tile::Snake {
+                    self.board.set_tile(old, Tile::Empty);
+                }
+            }
+            for i in 0..self.snake.cells.len() {
+                self.board.set_tile(self.snake.cells[i], Tile::Snake);
+            }
+        }
+    }
 
     mod new {
         use super::*;
 
         #[test]
         fn test_initial_snake_placement() {
-            let game = Game::new(10);
-            assert_eq!(game.snake, VecDeque::from([44]));
+            let game = game_with(GameConfig::new(10, 10));
+            assert_eq!(game.snake.cells, VecDeque::from([44]));
 
-            let game = Game::new(3);
-            assert_eq!(game.snake, VecDeque::from([4]));
+            let game = game_with(GameConfig::new(3, 3));
+            assert_eq!(game.snake.cells, VecDeque::from([4]));
         }
 
         #[test]
         fn test_initial_food_placement() {
-            let game = Game::new(3);
-            assert_ne!(game.food, 4);
-            assert!((0..9).contains(&game.food));
+            let game = game_with(GameConfig::new(3, 3));
+            assert_eq!(game.food.len(), 1);
+            assert_ne!(game.food[0].cell, 4);
+            assert!((0..9).contains(&game.food[0].cell));
+
+            let game = game_with(GameConfig::new(10, 10));
+            assert_eq!(game.food.len(), 1);
+            assert_ne!(game.food[0].cell, 44);
+            assert!((0..100).contains(&game.food[0].cell));
+        }
+    }
+
+    mod rectangular {
+        use super::*;
 
-            let game = Game::new(10);
-            assert_ne!(game.food, 44);
-            assert!((0..100).contains(&game.food));
+        #[test]
+        fn test_initial_snake_placement() {
+            let game = game_with(GameConfig::new(5, 3));
+            assert_eq!(game.snake.cells, VecDeque::from([7]));
+
+            let game = game_with(GameConfig::new(3, 8));
+            assert_eq!(game.snake.cells, VecDeque::from([10]));
+
+            let game = game_with(GameConfig::new(1, 20));
+            assert_eq!((game.width(), game.height()), (3, 20));
+        }
+
+        #[test]
+        fn test_wall_collisions() {
+            // Wide board: 2 moves reach the left wall, 3 the right wall
+            let mut game = game_with(GameConfig::new(5, 3));
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Left);
+            game.move_snake(Direction::Left);
+            assert!(!game.finished);
+            game.move_snake(Direction::Left);
+            assert_eq!(game.death_cause, Some(DeathCause::LeftRightWall));
+
+            let mut game = game_with(GameConfig::new(5, 3));
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Down);
+            game.move_snake(Direction::Down);
+            assert_eq!(game.death_cause, Some(DeathCause::TopBottomWall));
+
+            // Tall board: 3 moves reach the top wall, 4 the bottom wall
+            let mut game = game_with(GameConfig::new(3, 8));
+            game.set_food(food(&[2]));
+            for _ in 0..4 {
+                game.move_snake(Direction::Down);
+            }
+            assert!(!game.finished);
+            game.move_snake(Direction::Down);
+            assert_eq!(game.death_cause, Some(DeathCause::TopBottomWall));
+        }
+
+        #[test]
+        fn test_display() {
+            let mut game = game_with(GameConfig::new(5, 3));
+            game.set_food(food(&[0]));
+
+            let display = game.display();
+            let lines: Vec<_> = display.lines().collect();
+
+            assert_eq!(lines.len(), 6);
+            assert_eq!(lines[0], format!("|{}|", "-".repeat(15)));
+            assert_eq!(lines[1], format!("| \u{2022} {}|", " ".repeat(12)));
+            assert_eq!(
+                lines[2],
+                format!("|{} \u{25A1} {}|", " ".repeat(6), " ".repeat(6))
+            );
+            assert_eq!(lines[5], "Score: 0");
+        }
+    }
+
+    mod wrap {
+        use super::*;
+
+        #[test]
+        fn test_horizontal_wrap() {
+            let mut game = game_with(GameConfig {
+                boundary: Boundary::Wrap,
+                ..GameConfig::new(5, 3)
+            });
+            game.set_food(food(&[14]));
+
+            for _ in 0..3 {
+                game.move_snake(Direction::Left);
+            }
+            assert_eq!(game.snake.cells, VecDeque::from([5, 9]));
+
+            game.move_snake(Direction::Up);
+            game.move_snake(Direction::Right);
+            assert_eq!(game.snake.cells, VecDeque::from([4, 0]));
+            assert!(!game.finished);
+        }
+
+        #[test]
+        fn test_vertical_wrap() {
+            let mut game = game_with(GameConfig {
+                boundary: Boundary::Wrap,
+                ..GameConfig::new(5, 3)
+            });
+            game.set_food(food(&[0]));
+
+            game.move_snake(Direction::Up);
+            game.move_snake(Direction::Up);
+            assert_eq!(game.snake.cells, VecDeque::from([2, 12]));
+
+            game.move_snake(Direction::Left);
+            game.move_snake(Direction::Down);
+            assert_eq!(game.snake.cells, VecDeque::from([11, 1]));
+            assert!(!game.finished);
+        }
+
+        #[test]
+        fn test_self_collision_across_edge() {
+            let mut game = game_with(GameConfig {
+                boundary: Boundary::Wrap,
+                ..GameConfig::new(3, 3)
+            });
+            game.set_food(food(&[1]));
+
+            game.move_snake(Direction::Up);
+            game.move_snake(Direction::Up);
+            let outcome = game.move_snake(Direction::Down);
+            assert_eq!(outcome.death_cause, Some(DeathCause::SelfCollision));
+        }
+    }
+
+    mod level {
+        use super::*;
+
+        #[test]
+        fn test_initial_placement() {
+            let game = game_with(GameConfig::from_level(&"#S.\n...\n".parse().unwrap()));
+            assert_eq!((game.width(), game.height()), (3, 2));
+            assert_eq!(game.snake.cells, VecDeque::from([1]));
+            assert_eq!(game.walls(), &BTreeSet::from([0]));
+        }
+
+        #[test]
+        fn test_obstacle_collision() {
+            let mut game = game_with(GameConfig::from_level(&"...\n.S#\n...\n".parse().unwrap()));
+            game.set_food(food(&[0]));
+
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.death_cause, Some(DeathCause::Obstacle));
+            assert_eq!(game.snake.cells, VecDeque::from([4]));
+        }
+
+        #[test]
+        fn test_food_avoids_walls() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let level = "###\n#S.\n###\n".parse().unwrap();
+
+            for _ in 0..20 {
+                assert_eq!(food_cells(&Game::from_level(&mut rng, &level)), vec![5]);
+            }
+        }
+
+        #[test]
+        fn test_seeded_food_with_walls() {
+            // Walls inserted in different orders must not change where food goes
+            let foods = |walls: Vec<isize>| -> Vec<Vec<isize>> {
+                (0..20)
+                    .map(|seed| {
+                        let config = GameConfig {
+                            walls: walls.iter().cloned().collect(),
+                            food_count: 5,
+                            ..GameConfig::new(8, 8)
+                        };
+                        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+                        food_cells(&Game::from_config(&mut rng, config))
+                    })
+                    .collect()
+            };
+            let walls: Vec<isize> = (0..64).filter(|cell| cell % 3 == 1).collect();
+
+            let forward = foods(walls.clone());
+            assert_eq!(forward, foods(walls.iter().rev().cloned().collect()));
+            assert_eq!(forward, foods(walls));
+        }
+
+        #[test]
+        fn test_display() {
+            let mut game = game_with(GameConfig::from_level(&"#S.\n".parse().unwrap()));
+            game.set_food(food(&[2]));
+
+            let display = game.display();
+            assert_eq!(
+                display.lines().nth(1),
+                Some("|\u{2592}\u{2592}\u{2592} \u{25A1}  \u{2022} |")
+            );
+        }
+    }
+
+    mod config {
+        use super::*;
+
+        #[test]
+        fn test_spawn_with_heading() {
+            let game = game_with(GameConfig {
+                start_length: 4,
+                spawn: Some(23),
+                heading: Some(Direction::Up),
+                ..GameConfig::new(10, 10)
+            });
+
+            assert_eq!(game.snake.cells, VecDeque::from([53, 43, 33, 23]));
+            assert_eq!(game.heading(), Some(Direction::Up));
+            assert_eq!(game.snake.pending_growth, 0);
+        }
+
+        #[test]
+        fn test_spawn_against_wall() {
+            let mut game = game_with(GameConfig {
+                start_length: 4,
+                spawn: Some(11),
+                heading: Some(Direction::Right),
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[0]));
+
+            // Only one cell fits behind the head, the rest grows in
+            assert_eq!(game.snake.cells, VecDeque::from([10, 11]));
+
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Right);
+            assert_eq!(game.snake.cells, VecDeque::from([11, 12, 13, 14]));
+        }
+
+        #[test]
+        fn test_spawn_without_heading() {
+            let mut game = game_with(GameConfig {
+                start_length: 3,
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[0]));
+            assert_eq!(game.snake.cells, VecDeque::from([44]));
+            assert_eq!(game.heading(), None);
+
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Right);
+            assert_eq!(game.snake.cells, VecDeque::from([45, 46, 47]));
+            assert_eq!(game.heading(), Some(Direction::Right));
+        }
+
+        #[test]
+        fn test_growth() {
+            let mut game = game_with(GameConfig {
+                start_length: 1,
+                growth: 3,
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[45]));
+
+            // Growth starts on the move the food is eaten
+            game.move_snake(Direction::Right);
+            assert_eq!(game.snake.cells, VecDeque::from([44, 45]));
+
+            game.set_food(food(&[0]));
+            for _ in 0..4 {
+                game.move_snake(Direction::Down);
+            }
+            assert_eq!(game.snake.cells, VecDeque::from([55, 65, 75, 85]));
+        }
+
+        #[test]
+        fn test_no_room_for_food() {
+            let mut game = game_with(GameConfig {
+                width: 2,
+                height: 1,
+                start_length: 2,
+                heading: Some(Direction::Right),
+                spawn: Some(1),
+                ..GameConfig::new(3, 3)
+            });
+
+            assert_eq!(game.snake.cells, VecDeque::from([0, 1]));
+            assert!(game.food().is_empty());
+            assert!(game.finished());
+            assert_eq!(game.death_cause(), Some(DeathCause::BoardFull));
+            assert!(game.move_snake(Direction::Left).finished);
+        }
+
+        #[test]
+        fn test_no_growth() {
+            let mut game = game_with(GameConfig {
+                start_length: 1,
+                growth: 0,
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[45]));
+
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Down);
+            assert_eq!(game.score(), 1);
+            assert_eq!(game.snake.cells, VecDeque::from([55]));
+        }
+    }
+
+    mod reversal {
+        use super::*;
+
+        // Game whose snake has just moved right
+        fn moving_right(reversal: ReversalPolicy) -> Game {
+            let mut game = game_with(GameConfig {
+                reversal,
+                hunger: Some(Hunger::new(10, HungerRefill::Reset)),
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Right);
+            game
+        }
+
+        #[test]
+        fn test_die() {
+            let mut game = moving_right(ReversalPolicy::Die);
+
+            let outcome = game.move_snake(Direction::Left);
+            assert_eq!(outcome.death_cause, Some(DeathCause::SelfCollision));
+        }
+
+        #[test]
+        fn test_ignore() {
+            let mut game = moving_right(ReversalPolicy::Ignore);
+
+            let outcome = game.move_snake(Direction::Left);
+            assert!(!outcome.finished);
+            assert_eq!(game.snake.cells, VecDeque::from([44, 45]));
+            assert_eq!(game.heading(), Some(Direction::Right));
+            assert_eq!(game.moves_left(), Some(8));
+        }
+
+        #[test]
+        fn test_continue_straight() {
+            let mut game = moving_right(ReversalPolicy::ContinueStraight);
+
+            let outcome = game.move_snake(Direction::Left);
+            assert!(!outcome.finished);
+            assert_eq!(game.snake.cells, VecDeque::from([45, 46]));
+            assert_eq!(game.heading(), Some(Direction::Right));
+        }
+
+        #[test]
+        fn test_single_cell_snake_can_reverse() {
+            let mut game = game_with(GameConfig {
+                start_length: 1,
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Left);
+            assert!(!game.finished);
+            assert_eq!(game.snake.cells, VecDeque::from([44]));
         }
     }
 
@@ -168,44 +856,44 @@ mod tests {
 
         #[test]
         fn test_move() {
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
 
             game.move_snake(Direction::Left);
-            assert_eq!(game.snake, VecDeque::from([4, 3]));
+            assert_eq!(game.snake.cells, VecDeque::from([4, 3]));
 
             game.move_snake(Direction::Down);
-            assert_eq!(game.snake, VecDeque::from([3, 6]));
+            assert_eq!(game.snake.cells, VecDeque::from([3, 6]));
 
             game.move_snake(Direction::Right);
-            assert_eq!(game.snake, VecDeque::from([6, 7]));
+            assert_eq!(game.snake.cells, VecDeque::from([6, 7]));
 
             game.move_snake(Direction::Up);
-            assert_eq!(game.snake, VecDeque::from([7, 4]));
+            assert_eq!(game.snake.cells, VecDeque::from([7, 4]));
         }
 
         #[test]
         fn test_wall_collisions() {
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
             game.move_snake(Direction::Left);
             game.move_snake(Direction::Left);
             assert!(game.finished);
 
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
             game.move_snake(Direction::Up);
             game.move_snake(Direction::Up);
             assert!(game.finished);
 
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
             game.move_snake(Direction::Right);
             game.move_snake(Direction::Right);
             assert!(game.finished);
 
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
             game.move_snake(Direction::Down);
             game.move_snake(Direction::Down);
             assert!(game.finished);
@@ -213,8 +901,8 @@ mod tests {
 
         #[test]
         fn test_snake_collision() {
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
 
             game.move_snake(Direction::Left);
             game.move_snake(Direction::Right);
@@ -223,29 +911,505 @@ mod tests {
 
         #[test]
         fn test_food_collision() {
-            let mut game = Game::new(3);
-            game.food = 0;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
 
             game.move_snake(Direction::Left);
             game.move_snake(Direction::Up);
-            assert_eq!(game.snake.len(), 3);
-            assert_eq!(game.snake, VecDeque::from([4, 3, 0]));
+            assert_eq!(game.snake.cells.len(), 3);
+            assert_eq!(game.snake.cells, VecDeque::from([4, 3, 0]));
             assert_eq!(game.score, 1);
         }
     }
 
+    mod move_relative {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut game = game_with(GameConfig::new(10, 10));
+            game.set_food(food(&[0]));
+
+            game.move_relative(RelativeAction::Straight);
+            assert_eq!(game.head(), 34);
+            game.move_relative(RelativeAction::TurnLeft);
+            assert_eq!(game.head(), 33);
+            game.move_relative(RelativeAction::TurnLeft);
+            assert_eq!(game.head(), 43);
+            game.move_relative(RelativeAction::TurnRight);
+            assert_eq!(game.head(), 42);
+            assert_eq!(game.heading(), Some(Direction::Left));
+            assert!(!game.finished());
+        }
+    }
+
+    mod step_outcome {
+        use super::*;
+
+        #[test]
+        fn test_plain_move() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+
+            assert_eq!(game.move_snake(Direction::Left), StepOutcome::default());
+        }
+
+        #[test]
+        fn test_food_eaten() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[3]));
+
+            let outcome = game.move_snake(Direction::Left);
+            assert!(outcome.ate_food);
+            assert_eq!(outcome.score_delta, 1);
+            assert!(!outcome.finished);
+        }
+
+        #[test]
+        fn test_game_over() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+
+            game.move_snake(Direction::Up);
+            let outcome = game.move_snake(Direction::Up);
+            assert!(outcome.finished);
+            assert_eq!(outcome.death_cause, Some(DeathCause::TopBottomWall));
+            assert_eq!(game.snake.cells, VecDeque::from([4, 1]));
+
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+
+            game.move_snake(Direction::Left);
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.death_cause, Some(DeathCause::SelfCollision));
+        }
+
+        #[test]
+        fn test_move_after_game_over() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+
+            game.move_snake(Direction::Right);
+            game.move_snake(Direction::Right);
+
+            let outcome = game.move_snake(Direction::Left);
+            assert!(outcome.finished);
+            assert_eq!(outcome.death_cause, None);
+            assert_eq!(game.snake.cells, VecDeque::from([4, 5]));
+        }
+    }
+
+    mod death_cause {
+        use super::*;
+
+        #[test]
+        fn test_walls() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Left);
+            game.move_snake(Direction::Left);
+            assert_eq!(game.death_cause(), Some(DeathCause::LeftRightWall));
+
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Down);
+            game.move_snake(Direction::Down);
+            assert_eq!(game.death_cause(), Some(DeathCause::TopBottomWall));
+        }
+
+        #[test]
+        fn test_self_collision() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[0]));
+            assert_eq!(game.death_cause(), None);
+
+            game.move_snake(Direction::Up);
+            game.move_snake(Direction::Down);
+            assert_eq!(game.death_cause(), Some(DeathCause::SelfCollision));
+        }
+
+        #[test]
+        fn test_board_full() {
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_snake(VecDeque::from([0, 1, 2, 5, 4, 3, 6, 7]));
+            game.set_food(food(&[8]));
+
+            let outcome = game.move_snake(Direction::Right);
+            assert!(outcome.ate_food);
+            assert!(outcome.finished);
+            assert_eq!(outcome.death_cause, Some(DeathCause::BoardFull));
+            assert_eq!(game.death_cause(), Some(DeathCause::BoardFull));
+            assert_eq!(game.score(), 1);
+        }
+    }
+
+    mod hunger {
+        use super::*;
+
+        #[test]
+        fn test_starvation() {
+            let mut game = game_with(GameConfig {
+                hunger: Some(Hunger::new(3, HungerRefill::Reset)),
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[0]));
+            assert_eq!(game.moves_left(), Some(3));
+
+            assert!(!game.move_snake(Direction::Left).finished);
+            assert!(!game.move_snake(Direction::Down).finished);
+            assert_eq!(game.moves_left(), Some(1));
+
+            let outcome = game.move_snake(Direction::Right);
+            assert!(outcome.finished);
+            assert_eq!(outcome.death_cause, Some(DeathCause::Starvation));
+        }
+
+        #[test]
+        fn test_eating_refills() {
+            let mut game = game_with(GameConfig {
+                hunger: Some(Hunger::new(3, HungerRefill::Reset)),
+                ..GameConfig::new(10, 10)
+            });
+            game.set_food(food(&[43]));
+
+            game.move_snake(Direction::Left);
+            assert_eq!(game.moves_left(), Some(3));
+
+            game.set_food(food(&[0]));
+            game.move_snake(Direction::Down);
+            game.move_snake(Direction::Right);
+            assert!(!game.finished());
+        }
+
+        #[test]
+        fn test_unlimited() {
+            let mut game = game_with(GameConfig::new(10, 10));
+            game.set_food(food(&[0]));
+            assert_eq!(game.moves_left(), None);
+
+            for _ in 0..100 {
+                game.move_snake(Direction::Left);
+                game.move_snake(Direction::Down);
+                game.move_snake(Direction::Right);
+                game.move_snake(Direction::Up);
+            }
+
+            assert!(!game.finished());
+        }
+    }
+
+    mod food_kinds {
+        use super::*;
+
+        fn place(game: &mut Game, cell: isize, kind: FoodKind, ticks_left: Option<usize>) {
+            game.set_food(vec![Food {
+                cell,
+                kind,
+                ticks_left,
+            }]);
+        }
+
+        #[test]
+        fn test_spawn_weights() {
+            let game = game_with(GameConfig {
+                food_count: 40,
+                food_rules: FoodRules {
+                    normal_weight: 0.0,
+                    golden_weight: 1.0,
+                    timed_weight: 1.0,
+                    ..Default::default()
+                },
+                ..GameConfig::new(10, 10)
+            });
+            let golden = game.food.iter().filter(|f| f.kind == FoodKind::Golden);
+            let timed = game.food.iter().filter(|f| f.kind == FoodKind::Timed);
+
+            assert_eq!(golden.count() + timed.clone().count(), 40);
+            assert!(timed.clone().count() > 10);
+            assert!(timed.map(|f| f.ticks_left).all(|t| t == Some(20)));
+        }
+
+        #[test]
+        fn test_golden() {
+            let mut game = game_with(GameConfig::new(10, 10));
+            place(&mut game, 45, FoodKind::Golden, None);
+
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.eaten, Some(FoodKind::Golden));
+            assert_eq!(outcome.score_delta, 5);
+            assert_eq!(game.score(), 5);
+            assert_eq!(game.snake.cells.len(), 2);
+        }
+
+        #[test]
+        fn test_poison() {
+            let mut game = game_with(GameConfig {
+                food_rules: FoodRules {
+                    poison_shrink: 2,
+                    ..Default::default()
+                },
+                ..GameConfig::new(10, 10)
+            });
+            game.score = 3;
+            game.set_snake(VecDeque::from([41, 42, 43, 44]));
+            game.snake.pending_growth = 0;
+            place(&mut game, 45, FoodKind::Poison, None);
+
+            let outcome = game.move_snake(Direction::Right);
+            assert!(outcome.ate_food);
+            assert_eq!(outcome.eaten, Some(FoodKind::Poison));
+            assert_eq!(outcome.score_delta, -1);
+            assert_eq!(game.score(), 2);
+            assert_eq!(game.snake.cells, VecDeque::from([44, 45]));
+
+            // Poison never takes the head or pushes the score below zero
+            game.score = 0;
+            game.config.food_rules.poison_shrink = 10;
+            place(&mut game, 46, FoodKind::Poison, None);
+
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.score_delta, 0);
+            assert_eq!(game.snake.cells, VecDeque::from([46]));
+        }
+
+        #[test]
+        fn test_poison_does_not_feed() {
+            let mut game = game_with(GameConfig::new(10, 10));
+            game.snake.hunger = Some(Hunger::new(5, HungerRefill::Reset));
+            game.snake.moves_left = 2;
+            place(&mut game, 45, FoodKind::Poison, None);
+
+            game.move_snake(Direction::Right);
+            assert_eq!(game.moves_left(), Some(1));
+        }
+
+        #[test]
+        fn test_timed() {
+            let mut game = game_with(GameConfig::new(10, 10));
+            place(&mut game, 0, FoodKind::Timed, Some(2));
+
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.expired, 0);
+            assert_eq!(game.food[0].ticks_left, Some(1));
+
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.expired, 1);
+            assert_eq!(game.food.len(), 1);
+            assert_eq!(game.food[0].kind, FoodKind::Normal);
+
+            place(&mut game, 47, FoodKind::Timed, Some(1));
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.eaten, Some(FoodKind::Timed));
+            assert_eq!(outcome.expired, 0);
+            assert_eq!(outcome.score_delta, 1);
+
+            place(&mut game, 0, FoodKind::Timed, Some(0));
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.expired, 1);
+            assert!(game.food.iter().all(|food| food.kind != FoodKind::Timed));
+        }
+
+        #[test]
+        fn test_display() {
+            let mut game = game_with(GameConfig::new(10, 10));
+
+            for kind in [FoodKind::Golden, FoodKind::Poison, FoodKind::Timed] {
+                place(&mut game, 0, kind, None);
+                assert!(game.display().contains(kind.glyph()));
+            }
+        }
+    }
+
     mod food {
         use super::*;
+        use std::collections::BTreeMap;
+
+        #[test]
+        fn test_seeded_placement() {
+            let foods = |seed: u64| -> Vec<isize> {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                let mut game = Game::new(&mut rng, 10, 10);
+                let mut foods = food_cells(&game);
+
+                for direction in [Direction::Left, Direction::Up, Direction::Right] {
+                    let (x, y) = direction.value();
+                    game.set_food(food(&[game.head() + x + y * 10]));
+                    game.move_snake(direction);
+                    foods.extend(food_cells(&game));
+                }
+
+                foods
+            };
+
+            assert_eq!(foods(1), foods(1));
+            assert_ne!(foods(1), foods(2));
+        }
+
+        #[test]
+        fn test_uniform_placement() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let actual_histogram: BTreeMap<isize, usize> = (0..800)
+                .map(|_| Game::new(&mut rng, 3, 3).food[0].cell)
+                .fold(Default::default(), |mut histogram, food| {
+                    *histogram.entry(food).or_default() += 1;
+                    histogram
+                });
+
+            // Every cell but the snake's should be picked roughly equally often
+            assert_eq!(actual_histogram.len(), 8);
+            assert!(!actual_histogram.contains_key(&4));
+            assert!(actual_histogram.values().all(|&n| (70..130).contains(&n)));
+        }
 
         #[test]
         fn test_place_food() {
-            let mut game = Game::new(3);
-            game.food = 3;
+            let mut game = game_with(GameConfig::new(3, 3));
+            game.set_food(food(&[3]));
             game.move_snake(Direction::Left);
 
-            assert!((0..9).contains(&game.food));
-            assert_ne!(game.food, 4);
-            assert_ne!(game.food, 3);
+            assert_eq!(game.food.len(), 1);
+            assert!((0..9).contains(&game.food[0].cell));
+            assert_ne!(game.food[0].cell, 4);
+            assert_ne!(game.food[0].cell, 3);
+        }
+
+        #[test]
+        fn test_multiple_food() {
+            let mut game = game_with(GameConfig {
+                food_count: 3,
+                ..GameConfig::new(10, 10)
+            });
+            assert_eq!(game.food.len(), 3);
+            assert!(!food_cells(&game).contains(&44));
+
+            game.set_food(food(&[45, 0, 99]));
+            let outcome = game.move_snake(Direction::Right);
+            assert!(outcome.ate_food);
+            assert_eq!(game.score(), 1);
+            assert_eq!(game.food.len(), 3);
+            assert!(food_cells(&game).contains(&0) && food_cells(&game).contains(&99));
+            assert!(!food_cells(&game).contains(&45));
+            assert_eq!(game.display().matches('\u{2022}').count(), 3);
+        }
+
+        #[test]
+        fn test_food_left_when_board_is_full() {
+            let mut game = game_with(GameConfig {
+                food_count: 2,
+                ..GameConfig::new(3, 3)
+            });
+            game.set_snake(VecDeque::from([0, 1, 2, 5, 4, 3, 6]));
+            game.set_food(food(&[7, 8]));
+
+            let outcome = game.move_snake(Direction::Right);
+            assert!(!outcome.finished);
+            assert_eq!(food_cells(&game), vec![8]);
+
+            let outcome = game.move_snake(Direction::Right);
+            assert_eq!(outcome.death_cause, Some(DeathCause::BoardFull));
+        }
+
+        #[test]
+        fn test_far_from_head() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let config = GameConfig {
+                food_spawn: FoodSpawn::FarFromHead,
+                spawn: Some(0),
+                ..GameConfig::new(10, 10)
+            };
+
+            for _ in 0..50 {
+                let game = Game::from_config(&mut rng, config.clone());
+                assert!(game.distance(0, game.food[0].cell) >= 9);
+            }
+        }
+
+        #[test]
+        fn test_sequence() {
+            let config = GameConfig {
+                food_spawn: FoodSpawn::Sequence { seed: 7 },
+                food_count: 5,
+                ..GameConfig::new(10, 10)
+            };
+
+            let mut rng = ChaCha8Rng::seed_from_u64(1);
+            let game_a = Game::from_config(&mut rng, config.clone());
+            let mut rng = ChaCha8Rng::seed_from_u64(2);
+            let game_b = Game::from_config(&mut rng, config.clone());
+            assert_eq!(game_a.food, game_b.food);
+
+            let config = GameConfig {
+                food_spawn: FoodSpawn::Sequence { seed: 8 },
+                ..config
+            };
+            let game_c = Game::from_config(&mut rng, config);
+            assert_ne!(game_a.food, game_c.food);
+        }
+    }
+
+    mod grid {
+        use super::*;
+
+        // Checks every tile, the empty cell list and each empty cell's slot
+        // in it against the snake, food and walls
+        fn assert_in_sync(game: &Game) {
+            let area = game.width() * game.height();
+
+            for cell in 0..area {
+                let expected = if game.walls().contains(&cell) {
+                    Tile::Wall
+                } else if game.snake().contains(&cell) {
+                    Tile::Snake
+                } else if food_cells(game).contains(&cell) {
+                    Tile::Food
+                } else {
+                    Tile::Empty
+                };
+                assert_eq!(game.tile(cell), expected, "cell {}", cell);
+            }
+
+            let mut free = game.free_cells().to_vec();
+            free.sort_unstable();
+            let expected: Vec<isize> = (0..area)
+                .filter(|&cell| game.tile(cell) == Tile::Empty)
+                .collect();
+            assert_eq!(free, expected);
+
+            for (slot, &cell) in game.free_cells().iter().enumerate() {
+                assert_eq!(game.board.slots()[cell as usize], slot, "cell {}", cell);
+            }
+        }
+
+        #[test]
+        fn test() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let level = "#....\n..#..\n.S...\n.....\n...#.\n".parse().unwrap();
+            let config = GameConfig {
+                boundary: Boundary::Wrap,
+                food_count: 3,
+                food_rules: FoodRules {
+                    poison_weight: 1.0,
+                    timed_weight: 1.0,
+                    timed_ticks: 4,
+                    ..FoodRules::default()
+                },
+                ..GameConfig::from_level(&level)
+            };
+
+            let mut causes = Vec::new();
+            for _ in 0..20 {
+                let mut game = Game::from_config(&mut rng, config.clone());
+                assert_in_sync(&game);
+
+                // Including after the move that ends the game
+                while !game.finished() {
+                    game.move_snake(Direction::ALL[rng.gen_range(0..4)]);
+                    assert_in_sync(&game);
+                }
+                causes.push(game.death_cause().unwrap());
+            }
+            assert!(causes.contains(&DeathCause::SelfCollision));
         }
     }
 }
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+/// Limit on how many moves the snake may make without eating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hunger {
+    // Moves allowed before the first food
+    limit: usize,
+    // How the budget changes when food is eaten
+    refill: HungerRefill,
+}
+
+/// How a [`Hunger`] budget changes each time the snake eats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HungerRefill {
+    /// Reset the remaining moves to the limit.
+    Reset,
+    /// Add a fixed number of moves to whatever is left.
+    Add(usize),
+    /// Raise the limit by a fixed number of moves, then reset to it.
+    Grow(usize),
+}
+
+impl Hunger {
+    pub fn new(limit: usize, refill: HungerRefill) -> Hunger {
+        assert!(limit > 0);
+
+        Hunger { limit, refill }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the number of moves left after eating with `moves_left`
+    /// remaining, growing the limit if the refill rule asks for it.
+    pub(crate) fn refill(&mut self, moves_left: usize) -> usize {
+        match self.refill {
+            HungerRefill::Reset => self.limit,
+            HungerRefill::Add(moves) => moves_left + moves,
+            HungerRefill::Grow(moves) => {
+                self.limit += moves;
+                self.limit
+            }
+        }
+    }
+}
+
+// Serializes an optional hunger limit, writing no limit as "off" since TOML
+// has no null
+pub(crate) mod optional {
+    use std::fmt;
+
+    use serde::de::{self, MapAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Hunger;
+
+    pub(crate) fn serialize<S: Serializer>(
+        hunger: &Option<Hunger>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match hunger {
+            Some(hunger) => hunger.serialize(serializer),
+            None => serializer.serialize_str("off"),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Hunger>, D::Error> {
+        struct HungerVisitor;
+
+        impl<'de> Visitor<'de> for HungerVisitor {
+            type Value = Option<Hunger>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a hunger table or \"off\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Option<Hunger>, E> {
+                match value {
+                    "off" => Ok(None),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+                }
+            }
+
+            // Configs saved before "off" existed wrote no limit as null
+            fn visit_unit<E: de::Error>(self) -> Result<Option<Hunger>, E> {
+                Ok(None)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Option<Hunger>, A::Error> {
+                Hunger::deserialize(de::value::MapAccessDeserializer::new(map)).map(Some)
+            }
+        }
+
+        deserializer.deserialize_any(HungerVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refill() {
+        let mut hunger = Hunger::new(10, HungerRefill::Reset);
+        assert_eq!(hunger.refill(3), 10);
+        assert_eq!(hunger.refill(7), 10);
+
+        let mut hunger = Hunger::new(10, HungerRefill::Add(5));
+        assert_eq!(hunger.refill(3), 8);
+        assert_eq!(hunger.limit(), 10);
+
+        let mut hunger = Hunger::new(10, HungerRefill::Grow(5));
+        assert_eq!(hunger.refill(3), 15);
+        assert_eq!(hunger.refill(3), 20);
+        assert_eq!(hunger.limit(), 20);
+    }
+}
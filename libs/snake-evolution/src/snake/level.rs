@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::snake::direction::Direction;
+
+/// Static board layout parsed from a plain-text level.
+///
+/// Each line is a row of the board, with one character per cell:
+///
+/// - `.` floor
+/// - `#` wall
+/// - `S` floor where the snake starts
+/// - `^`, `v`, `<` or `>` floor where the snake starts, facing that way
+///
+/// Exactly one start cell is required and every row must be the same width.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Level {
+    width: isize,
+    height: isize,
+    walls: BTreeSet<isize>,
+    start: isize,
+    heading: Option<Direction>,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Empty,
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownTile {
+        row: usize,
+        column: usize,
+        tile: char,
+    },
+    MissingStart,
+    MultipleStarts,
+}
+
+impl Level {
+    /// Reads and parses the level file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Level, LevelError> {
+        std::fs::read_to_string(path)
+            .map_err(LevelError::Io)?
+            .parse()
+    }
+
+    pub fn width(&self) -> isize {
+        self.width
+    }
+
+    pub fn height(&self) -> isize {
+        self.height
+    }
+
+    /// Cells holding a wall.
+    pub fn walls(&self) -> &BTreeSet<isize> {
+        &self.walls
+    }
+
+    /// Cell the snake starts on.
+    pub fn start(&self) -> isize {
+        self.start
+    }
+
+    /// Direction the snake starts facing, if the level sets one.
+    pub fn heading(&self) -> Option<Direction> {
+        self.heading
+    }
+}
+
+impl FromStr for Level {
+    type Err = LevelError;
+
+    fn from_str(s: &str) -> Result<Level, LevelError> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(str::trim_end)
+            .filter(|row| !row.is_empty())
+            .collect();
+
+        let width = rows.first().ok_or(LevelError::Empty)?.chars().count();
+        let mut walls = BTreeSet::new();
+        let mut start = None;
+
+        for (y, row) in rows.iter().enumerate() {
+            let found = row.chars().count();
+            if found != width {
+                return Err(LevelError::RaggedRow {
+                    row: y + 1,
+                    expected: width,
+                    found,
+                });
+            }
+
+            for (x, tile) in row.chars().enumerate() {
+                let i = (y * width + x) as isize;
+
+                let heading = match tile {
+                    '.' => continue,
+                    '#' => {
+                        walls.insert(i);
+                        continue;
+                    }
+                    'S' => None,
+                    '^' => Some(Direction::Up),
+                    'v' => Some(Direction::Down),
+                    '<' => Some(Direction::Left),
+                    '>' => Some(Direction::Right),
+                    _ => {
+                        return Err(LevelError::UnknownTile {
+                            row: y + 1,
+                            column: x + 1,
+                            tile,
+                        })
+                    }
+                };
+
+                if start.replace((i, heading)).is_some() {
+                    return Err(LevelError::MultipleStarts);
+                }
+            }
+        }
+
+        let (start, heading) = start.ok_or(LevelError::MissingStart)?;
+
+        Ok(Level {
+            width: width as isize,
+            height: rows.len() as isize,
+            walls,
+            start,
+            heading,
+        })
+    }
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "could not read level: {}", err),
+            LevelError::Empty => write!(f, "level has no rows"),
+            LevelError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} is {} cells wide, expected {}",
+                row, found, expected
+            ),
+            LevelError::UnknownTile { row, column, tile } => write!(
+                f,
+                "unknown tile {:?} at row {}, column {}",
+                tile, row, column
+            ),
+            LevelError::MissingStart => write!(f, "level has no start cell"),
+            LevelError::MultipleStarts => write!(f, "level has more than one start cell"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LevelError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let level: Level = "\
+                #####\n\
+                #.>.#\n\
+                #####\n"
+                .parse()
+                .unwrap();
+
+            assert_eq!(level.width(), 5);
+            assert_eq!(level.height(), 3);
+            assert_eq!(level.start(), 7);
+            assert_eq!(level.heading(), Some(Direction::Right));
+            assert_eq!(level.walls().len(), 12);
+            assert!(!level.walls().contains(&6));
+            assert!(level.walls().contains(&9));
+        }
+
+        #[test]
+        fn test_start_without_heading() {
+            let level: Level = "S..\n...\r\n".parse().unwrap();
+
+            assert_eq!(level.start(), 0);
+            assert_eq!(level.heading(), None);
+            assert!(level.walls().is_empty());
+        }
+
+        #[test]
+        fn test_errors() {
+            assert!(matches!("".parse::<Level>(), Err(LevelError::Empty)));
+            assert!(matches!(
+                "S..\n..\n".parse::<Level>(),
+                Err(LevelError::RaggedRow {
+                    row: 2,
+                    expected: 3,
+                    found: 2
+                })
+            ));
+            assert!(matches!(
+                "S.x\n".parse::<Level>(),
+                Err(LevelError::UnknownTile {
+                    row: 1,
+                    column: 3,
+                    tile: 'x'
+                })
+            ));
+            assert!(matches!(
+                "...\n".parse::<Level>(),
+                Err(LevelError::MissingStart)
+            ));
+            assert!(matches!(
+                "S.^\n".parse::<Level>(),
+                Err(LevelError::MultipleStarts)
+            ));
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/levels/pillars.txt");
+            let level = Level::load(path).unwrap();
+
+            assert_eq!(level.width(), 12);
+            assert_eq!(level.height(), 10);
+            assert_eq!(level.walls().len(), 16);
+            assert_eq!(level.heading(), Some(Direction::Up));
+        }
+    }
+}
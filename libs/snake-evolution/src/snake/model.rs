@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use lib_genetic_algorithm::Individual;
+use lib_neural_network::{Activation, LayerTopology, Network};
+use serde::{Deserialize, Serialize};
+
+use crate::snake::agent::{Evaluator, SnakeAgent};
+use crate::snake::config::GameConfig;
+use crate::snake::save::{self, SaveError};
+
+/// A trained agent, saved with the rules it was trained on so it can be
+/// played again.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Model {
+    /// Rules of the games the agent was trained on.
+    pub config: GameConfig,
+    /// Number of neurons in every layer of the agent's network.
+    pub layers: Vec<usize>,
+    /// Activation of every layer of the agent's network after the first.
+    pub activations: Vec<Activation>,
+    /// Genes of the agent, which are the weights of its network.
+    pub weights: Vec<f32>,
+}
+
+impl Model {
+    /// Model of `agent`, scored by `evaluator`.
+    pub fn new(evaluator: &Evaluator, agent: &SnakeAgent) -> Model {
+        Model {
+            config: evaluator.config.clone(),
+            layers: evaluator
+                .topology
+                .iter()
+                .map(|layer| layer.neurons)
+                .collect(),
+            activations: evaluator.activations.clone(),
+            weights: agent.chromosome().iter().cloned().collect(),
+        }
+    }
+
+    /// Reads the model saved at `path`, checking that its network fits its
+    /// rules.
+    pub fn load(path: impl AsRef<Path>) -> Result<Model, SaveError> {
+        let model: Model = save::load(path)?;
+        model.validate()?;
+
+        Ok(model)
+    }
+
+    /// Writes the model to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        save::save(self, path)
+    }
+
+    /// Shape of the agent's network.
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        self.layers
+            .iter()
+            .map(|&neurons| LayerTopology { neurons })
+            .collect()
+    }
+
+    /// Network that controls the agent.
+    pub fn network(&self) -> Network {
+        Network::from_weights(&self.topology(), self.weights.iter().cloned())
+            .with_activations(&self.activations)
+    }
+
+    fn validate(&self) -> Result<(), SaveError> {
+        self.config
+            .validate()
+            .map_err(|err| SaveError::Invalid(format!("config.{}", err)))?;
+
+        let inputs = self.config.observer().size();
+        let outputs = self.config.actions.size();
+
+        if self.layers.len() < 2 || self.layers.contains(&0) {
+            return Err(SaveError::Invalid(
+                "network needs at least two layers, none empty".to_string(),
+            ));
+        }
+        if self.activations.len() != self.layers.len() - 1 {
+            return Err(SaveError::Invalid(format!(
+                "network has {} layers after its inputs, but {} activations",
+                self.layers.len() - 1,
+                self.activations.len()
+            )));
+        }
+        if self.layers[0] != inputs {
+            return Err(SaveError::Invalid(format!(
+                "network has {} inputs, but the game gives {} observations",
+                self.layers[0], inputs
+            )));
+        }
+        if self.layers[self.layers.len() - 1] != outputs {
+            return Err(SaveError::Invalid(format!(
+                "network has {} outputs, but the game has {} actions",
+                self.layers[self.layers.len() - 1],
+                outputs
+            )));
+        }
+
+        let expected = Network::weight_count(&self.topology());
+        if self.weights.len() != expected {
+            return Err(SaveError::Invalid(format!(
+                "network needs {} weights, found {}",
+                expected,
+                self.weights.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::direction::ActionSpace;
+    use crate::snake::fitness::ScoreOnly;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn new_model() -> Model {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let evaluator = Evaluator {
+            config: GameConfig {
+                actions: ActionSpace::Relative,
+                ..GameConfig::new(4, 4)
+            },
+            topology: vec![
+                LayerTopology { neurons: 16 },
+                LayerTopology { neurons: 5 },
+                LayerTopology { neurons: 3 },
+            ],
+            activations: vec![Activation::Tanh, Activation::Linear],
+            fitness: Box::new(ScoreOnly),
+            max_steps: 100,
+        };
+        let agent = SnakeAgent::random(&mut rng, &evaluator.topology);
+
+        Model::new(&evaluator, &agent)
+    }
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("snake-model-{}-{}", std::process::id(), name))
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let model = new_model();
+
+            assert_eq!(model.layers, vec![16, 5, 3]);
+            assert_eq!(model.weights.len(), 17 * 5 + 6 * 3);
+            assert!(model.network().weights().eq(model.weights.iter().cloned()));
+            assert_eq!(model.network().activations(), model.activations.as_slice());
+            assert!(model.validate().is_ok());
+        }
+    }
+
+    mod load {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let model = new_model();
+            let path = path("round-trip");
+
+            model.save(&path).unwrap();
+            let loaded = Model::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded, model);
+        }
+
+        #[test]
+        fn test_errors() {
+            let path = path("errors");
+
+            assert!(matches!(Model::load(&path), Err(SaveError::Io(_))));
+
+            std::fs::write(&path, "{ \"layers\": [16, 3] }").unwrap();
+            assert!(matches!(Model::load(&path), Err(SaveError::Json(_))));
+
+            let mut model = new_model();
+            model.layers[0] = 15;
+            model.save(&path).unwrap();
+            assert!(matches!(Model::load(&path), Err(SaveError::Invalid(_))));
+
+            let mut model = new_model();
+            model.activations.pop();
+            model.save(&path).unwrap();
+            assert!(matches!(Model::load(&path), Err(SaveError::Invalid(_))));
+
+            let mut model = new_model();
+            model.weights.pop();
+            model.save(&path).unwrap();
+            assert!(matches!(Model::load(&path), Err(SaveError::Invalid(_))));
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,562 @@
+use serde::{Deserialize, Serialize};
+
+use crate::snake::direction::Direction;
+use crate::snake::game::{Boundary, Game, Tile};
+
+/// Turns the state of a game into inputs for a network.
+pub trait Observer {
+    /// Number of values written for every game.
+    fn size(&self) -> usize;
+
+    /// Lowest and highest value ever written.
+    fn bounds(&self) -> (f32, f32);
+
+    /// Writes what `game` looks like into `out`, which holds exactly
+    /// [`size`](Observer::size) values.
+    fn observe(&self, game: &Game, out: &mut [f32]);
+
+    /// What `game` looks like, as a new vector.
+    fn observation(&self, game: &Game) -> Vec<f32> {
+        let mut out = vec![0.0; self.size()];
+        self.observe(game, &mut out);
+
+        out
+    }
+}
+
+/// Observes the whole board with one value for every cell: `1.0` for food,
+/// `-1.0` for walls and the snake, and `0.0` for empty cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileObserver {
+    width: isize,
+    height: isize,
+}
+
+impl TileObserver {
+    /// Observer for games on a `width` by `height` board.
+    pub fn new(width: isize, height: isize) -> TileObserver {
+        assert!(width > 0 && height > 0);
+
+        TileObserver { width, height }
+    }
+}
+
+impl Observer for TileObserver {
+    fn size(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        (-1.0, 1.0)
+    }
+
+    fn observe(&self, game: &Game, out: &mut [f32]) {
+        assert_eq!((game.width(), game.height()), (self.width, self.height));
+        assert_eq!(out.len(), self.size());
+
+        for (cell, value) in out.iter_mut().enumerate() {
+            *value = match game.tile(cell as isize) {
+                Tile::Empty => 0.0,
+                Tile::Food => 1.0,
+                Tile::Wall | Tile::Snake => -1.0,
+            };
+        }
+    }
+}
+
+/// Observes the board by casting rays outwards from the head of the snake.
+///
+/// Every ray reports three values: how close the nearest wall, body cell and
+/// food along it are, each as `1 / distance` in moves, or `0.0` if the ray
+/// does not reach one. Rays stop at the first wall, and on wrapping boards
+/// once they have crossed the whole board. After the rays come the current
+/// heading and the direction the tail is moving in, each one-hot over
+/// [`Direction::ALL`], or all zeros while the snake has no heading.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RayVision {
+    rays: Vec<(isize, isize)>,
+}
+
+impl RayVision {
+    /// Values reported for every ray.
+    pub const VALUES_PER_RAY: usize = 3;
+
+    /// Number of values written with the default eight rays.
+    pub const SIZE: usize = 8 * RayVision::VALUES_PER_RAY + 2 * 4;
+
+    /// Vision along `rays`, each given as the `(x, y)` offset of one step,
+    /// with `y` growing downwards.
+    pub fn new(rays: Vec<(isize, isize)>) -> RayVision {
+        assert!(!rays.is_empty());
+        assert!(rays.iter().all(|&ray| ray != (0, 0)));
+
+        RayVision { rays }
+    }
+
+    /// Offsets of the rays, in the order they are reported.
+    pub fn rays(&self) -> &[(isize, isize)] {
+        &self.rays
+    }
+
+    // Reports the nearest wall, body cell and food along `ray` into `out`
+    fn cast(&self, game: &Game, (dx, dy): (isize, isize), out: &mut [f32]) {
+        let (width, height) = (game.width(), game.height());
+        let head = game.head();
+        let (mut x, mut y) = (head % width, head / width);
+        let (mut wall, mut body, mut food) = (0.0, 0.0, 0.0);
+
+        for distance in 1..=width.max(height) {
+            (x, y) = (x + dx, y + dy);
+            if !(0..width).contains(&x) || !(0..height).contains(&y) {
+                match game.boundary() {
+                    Boundary::Walls => {
+                        wall = 1.0 / distance as f32;
+                        break;
+                    }
+                    Boundary::Wrap => (x, y) = (x.rem_euclid(width), y.rem_euclid(height)),
+                }
+            }
+
+            let cell = y * width + x;
+            if cell == head {
+                break;
+            }
+
+            let seen = 1.0 / distance as f32;
+            match game.tile(cell) {
+                Tile::Wall => {
+                    wall = seen;
+                    break;
+                }
+                Tile::Snake if body == 0.0 => body = seen,
+                Tile::Food if food == 0.0 => food = seen,
+                _ => {}
+            }
+        }
+
+        out.copy_from_slice(&[wall, body, food]);
+    }
+}
+
+impl Default for RayVision {
+    /// Eight rays, clockwise from straight up.
+    fn default() -> RayVision {
+        RayVision::new(vec![
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ])
+    }
+}
+
+impl Observer for RayVision {
+    fn size(&self) -> usize {
+        self.rays.len() * RayVision::VALUES_PER_RAY + 2 * Direction::ALL.len()
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        (0.0, 1.0)
+    }
+
+    fn observe(&self, game: &Game, out: &mut [f32]) {
+        assert_eq!(out.len(), self.size());
+
+        let (rays, directions) = out.split_at_mut(self.rays.len() * RayVision::VALUES_PER_RAY);
+        for (&ray, out) in self
+            .rays
+            .iter()
+            .zip(rays.chunks_mut(RayVision::VALUES_PER_RAY))
+        {
+            self.cast(game, ray, out);
+        }
+
+        // The tail moves towards the next cell of the body, or follows the
+        // head when the snake is a single cell
+        let snake = game.snake();
+        let tail = match (snake.front(), snake.get(1)) {
+            (Some(&tail), Some(&next)) => Direction::ALL
+                .into_iter()
+                .find(|&direction| game.config().advance(tail, direction) == Ok(next)),
+            _ => game.heading(),
+        };
+
+        let (heading, tail_out) = directions.split_at_mut(Direction::ALL.len());
+        for (i, direction) in Direction::ALL.into_iter().enumerate() {
+            heading[i] = if game.heading() == Some(direction) {
+                1.0
+            } else {
+                0.0
+            };
+            tail_out[i] = if tail == Some(direction) { 1.0 } else { 0.0 };
+        }
+    }
+}
+
+/// How the body channel of a [`GridEncoder`] marks the snake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyEncoding {
+    /// Every body cell is `1.0`.
+    #[default]
+    Flat,
+    /// Body cells fade from nearly `1.0` behind the head to `1 / length` at
+    /// the tail, so the channel also shows which way the body moves.
+    Gradient,
+}
+
+/// Observes the board as a stack of channels with one value for every cell:
+/// the head, the body without the head, food and walls, in that order.
+///
+/// Values are written a channel at a time, each channel row by row. With a
+/// crop the grid is a square of `2 * radius + 1` cells centred on the head,
+/// where cells beyond a walled edge of the board show up as walls and
+/// wrapping boards repeat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridEncoder {
+    width: isize,
+    height: isize,
+    body: BodyEncoding,
+    crop: Option<isize>,
+}
+
+impl GridEncoder {
+    /// Number of channels in the grid.
+    pub const CHANNELS: usize = 4;
+
+    /// Encoder for games on a `width` by `height` board, either for the
+    /// whole board or cropped to `crop` cells around the head.
+    pub fn new(
+        width: isize,
+        height: isize,
+        body: BodyEncoding,
+        crop: Option<isize>,
+    ) -> GridEncoder {
+        assert!(width > 0 && height > 0);
+        assert!(crop.is_none_or(|radius| radius >= 0));
+
+        GridEncoder {
+            width,
+            height,
+            body,
+            crop,
+        }
+    }
+
+    /// Number of columns and rows in every channel.
+    pub fn shape(&self) -> (usize, usize) {
+        match self.crop {
+            Some(radius) => ((2 * radius + 1) as usize, (2 * radius + 1) as usize),
+            None => (self.width as usize, self.height as usize),
+        }
+    }
+}
+
+impl Observer for GridEncoder {
+    fn size(&self) -> usize {
+        let (columns, rows) = self.shape();
+
+        GridEncoder::CHANNELS * columns * rows
+    }
+
+    fn bounds(&self) -> (f32, f32) {
+        (0.0, 1.0)
+    }
+
+    fn observe(&self, game: &Game, out: &mut [f32]) {
+        let (width, height) = (self.width, self.height);
+        assert_eq!((game.width(), game.height()), (width, height));
+        assert_eq!(out.len(), self.size());
+
+        let head = game.head();
+        let (columns, rows) = self.shape();
+        let (left, top) = match self.crop {
+            Some(radius) => (head % width - radius, head / width - radius),
+            None => (0, 0),
+        };
+
+        // Age of every body cell, counting the tail as 1
+        let snake = game.snake();
+        let mut ages = Vec::new();
+        if self.body == BodyEncoding::Gradient {
+            ages = vec![0.0; (width * height) as usize];
+            for (age, &cell) in snake.iter().enumerate() {
+                ages[cell as usize] = (age + 1) as f32 / snake.len() as f32;
+            }
+        }
+
+        out.fill(0.0);
+        let (head_out, rest) = out.split_at_mut(columns * rows);
+        let (body_out, rest) = rest.split_at_mut(columns * rows);
+        let (food_out, wall_out) = rest.split_at_mut(columns * rows);
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let i = row * columns + column;
+                let (mut x, mut y) = (left + column as isize, top + row as isize);
+
+                if !(0..width).contains(&x) || !(0..height).contains(&y) {
+                    match game.boundary() {
+                        Boundary::Walls => {
+                            wall_out[i] = 1.0;
+                            continue;
+                        }
+                        Boundary::Wrap => (x, y) = (x.rem_euclid(width), y.rem_euclid(height)),
+                    }
+                }
+
+                let cell = y * width + x;
+                match game.tile(cell) {
+                    _ if cell == head => head_out[i] = 1.0,
+                    Tile::Snake => {
+                        body_out[i] = match self.body {
+                            BodyEncoding::Flat => 1.0,
+                            BodyEncoding::Gradient => ages[cell as usize],
+                        }
+                    }
+                    Tile::Food => food_out[i] = 1.0,
+                    Tile::Wall => wall_out[i] = 1.0,
+                    Tile::Empty => {}
+                }
+            }
+        }
+    }
+}
+
+/// Built-in ways of observing a game, selectable in a
+/// [`GameConfig`](crate::snake::config::GameConfig).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    /// One value for every cell, see [`TileObserver`].
+    #[default]
+    Tiles,
+    /// Rays cast from the head, see [`RayVision`].
+    Rays(RayVision),
+    /// A stack of channels over the board or around the head, see
+    /// [`GridEncoder`].
+    Grid {
+        body: BodyEncoding,
+        crop: Option<isize>,
+    },
+}
+
+impl Encoding {
+    /// Observer for games on a `width` by `height` board.
+    pub fn observer(&self, width: isize, height: isize) -> Box<dyn Observer> {
+        match self {
+            Encoding::Tiles => Box::new(TileObserver::new(width, height)),
+            Encoding::Rays(vision) => Box::new(vision.clone()),
+            Encoding::Grid { body, crop } => {
+                Box::new(GridEncoder::new(width, height, *body, *crop))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snake::config::GameConfig;
+    use crate::snake::fixtures::{food, game_with};
+
+    mod tile_observer {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let level = "#..\n.>.\n...\n".parse().unwrap();
+            let game = game_with(GameConfig::from_level(&level));
+            let observer = TileObserver::new(3, 3);
+
+            let observation = observer.observation(&game);
+
+            assert_eq!(observer.size(), 9);
+            assert_eq!(observation[0], -1.0);
+            assert_eq!(&observation[3..5], &[-1.0, -1.0]);
+            assert_eq!(observation[game.food()[0].cell as usize], 1.0);
+            assert_eq!(observation.iter().filter(|&&value| value == 0.0).count(), 5);
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_wrong_board() {
+            let game = game_with(GameConfig::new(4, 4));
+
+            TileObserver::new(3, 3).observation(&game);
+        }
+    }
+    mod ray_vision {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let level = ".....\n.....\n..>..\n.....\n..#..\n".parse().unwrap();
+            let mut game = game_with(GameConfig::from_level(&level));
+            game.set_food(food(&[2]));
+            let vision = RayVision::default();
+
+            let third = 1.0 / 3.0;
+            #[rustfmt::skip]
+            let expected = vec![
+                third, 0.0, 0.5,
+                third, 0.0, 0.0,
+                third, 0.0, 0.0,
+                third, 0.0, 0.0,
+                0.5, 0.0, 0.0,
+                third, 0.0, 0.0,
+                third, 1.0, 0.0,
+                third, 0.0, 0.0,
+                0.0, 0.0, 0.0, 1.0,
+                0.0, 0.0, 0.0, 1.0,
+            ];
+
+            assert_eq!(vision.size(), RayVision::SIZE);
+            assert_eq!(vision.observation(&game), expected);
+        }
+
+        #[test]
+        fn test_wrap() {
+            let mut game = game_with(GameConfig {
+                boundary: Boundary::Wrap,
+                ..GameConfig::new(5, 5)
+            });
+            game.set_food(food(&[14]));
+            let vision = RayVision::new(vec![(0, -1), (1, 0)]);
+
+            // Rays stop when they come back round to the head
+            assert_eq!(
+                vision.observation(&game),
+                vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+            );
+
+            game.move_snake(Direction::Left);
+            assert_eq!(
+                vision.observation(&game),
+                vec![
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    1.0 / 3.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    1.0,
+                    0.0
+                ]
+            );
+        }
+
+        #[test]
+        fn test_encoding() {
+            let game = game_with(GameConfig {
+                observation: Encoding::Rays(RayVision::default()),
+                ..GameConfig::new(6, 6)
+            });
+
+            assert_eq!(game.observe().len(), RayVision::SIZE);
+        }
+    }
+    mod grid_encoder {
+        use super::*;
+        use std::collections::VecDeque;
+
+        fn level_game() -> Game {
+            let level = "#...\n.>..\n....\n".parse().unwrap();
+            let mut game = game_with(GameConfig::from_level(&level));
+            game.set_food(food(&[11]));
+
+            game
+        }
+
+        // Values of `channel`, given the number of cells in each
+        fn channel(observation: &[f32], channel: usize, cells: usize) -> &[f32] {
+            &observation[channel * cells..(channel + 1) * cells]
+        }
+
+        #[test]
+        fn test() {
+            let game = level_game();
+            let encoder = GridEncoder::new(4, 3, BodyEncoding::Flat, None);
+            let observation = encoder.observation(&game);
+
+            assert_eq!(encoder.shape(), (4, 3));
+            assert_eq!(encoder.size(), 48);
+            for (i, cell) in [5, 4, 11, 0].into_iter().enumerate() {
+                let mut expected = vec![0.0; 12];
+                expected[cell] = 1.0;
+                assert_eq!(channel(&observation, i, 12), expected);
+            }
+        }
+
+        #[test]
+        fn test_gradient() {
+            let mut game = level_game();
+            game.set_snake(VecDeque::from([8, 4, 5]));
+            let encoder = GridEncoder::new(4, 3, BodyEncoding::Gradient, None);
+            let observation = encoder.observation(&game);
+
+            let body = channel(&observation, 1, 12);
+            assert_eq!(body[8], 1.0 / 3.0);
+            assert_eq!(body[4], 2.0 / 3.0);
+            assert_eq!(body[5], 0.0);
+        }
+
+        #[test]
+        fn test_crop() {
+            let game = level_game();
+            let encoder = GridEncoder::new(4, 3, BodyEncoding::Flat, Some(2));
+            let observation = encoder.observation(&game);
+
+            // The head sits in the middle of a 5x5 window reaching past the
+            // top, bottom and left edges
+            assert_eq!(encoder.shape(), (5, 5));
+            assert_eq!(channel(&observation, 0, 25)[12], 1.0);
+            assert_eq!(channel(&observation, 1, 25)[11], 1.0);
+            assert_eq!(channel(&observation, 2, 25)[19], 1.0);
+
+            let walls = channel(&observation, 3, 25);
+            assert_eq!(walls.iter().sum::<f32>(), 14.0);
+            assert_eq!(walls[6], 1.0);
+            assert_eq!(walls[7], 0.0);
+        }
+
+        #[test]
+        fn test_crop_wrap() {
+            let mut game = game_with(GameConfig {
+                boundary: Boundary::Wrap,
+                ..GameConfig::new(3, 3)
+            });
+            game.set_food(food(&[0]));
+            let encoder = GridEncoder::new(3, 3, BodyEncoding::Flat, Some(2));
+            let observation = encoder.observation(&game);
+
+            assert_eq!(channel(&observation, 0, 25).iter().sum::<f32>(), 1.0);
+            assert_eq!(channel(&observation, 2, 25).iter().sum::<f32>(), 4.0);
+            assert_eq!(channel(&observation, 3, 25).iter().sum::<f32>(), 0.0);
+        }
+
+        #[test]
+        fn test_encoding() {
+            let game = game_with(GameConfig {
+                observation: Encoding::Grid {
+                    body: BodyEncoding::Gradient,
+                    crop: Some(3),
+                },
+                ..GameConfig::new(10, 10)
+            });
+
+            assert_eq!(game.observe().len(), 4 * 7 * 7);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::Path;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::snake::config::GameConfig;
+use crate::snake::direction::Direction;
+use crate::snake::game::Game;
+use crate::snake::save::{self, SaveError};
+
+/// A recorded game: its rules, the seed it was started from and every move
+/// made in it.
+///
+/// Games are deterministic given their seed, so this is enough to play one
+/// back exactly.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub config: GameConfig,
+    pub seed: u64,
+    pub moves: Vec<Direction>,
+}
+
+impl Replay {
+    /// Empty recording of a game following `config`, started from `seed`.
+    pub fn new(config: GameConfig, seed: u64) -> Replay {
+        Replay {
+            config,
+            seed,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Reads the replay saved at `path`, checking that its rules describe a
+    /// game that can be started.
+    pub fn load(path: impl AsRef<Path>) -> Result<Replay, SaveError> {
+        let replay: Replay = save::load(path)?;
+        replay
+            .config
+            .validate()
+            .map_err(|err| SaveError::Invalid(format!("config.{}", err)))?;
+
+        Ok(replay)
+    }
+
+    /// Writes the replay to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        save::save(self, path)
+    }
+
+    /// The recorded game before its first move.
+    pub fn start(&self) -> Game {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+
+        Game::from_config(&mut rng, self.config.clone())
+    }
+
+    /// The recorded game after every move has been made again.
+    pub fn finish(&self) -> Game {
+        let mut game = self.start();
+        for &direction in &self.moves {
+            game.move_snake(direction);
+        }
+
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_replay() -> Replay {
+        let mut replay = Replay::new(GameConfig::new(5, 5), 3);
+        let mut game = replay.start();
+
+        for direction in [Direction::Left, Direction::Up, Direction::Up, Direction::Up] {
+            game.move_snake(direction);
+            replay.moves.push(direction);
+        }
+
+        replay
+    }
+
+    mod start {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let replay = new_replay();
+            let mut rng = ChaCha8Rng::seed_from_u64(3);
+            let game = Game::from_config(&mut rng, GameConfig::new(5, 5));
+
+            assert_eq!(replay.start().snake(), game.snake());
+            assert_eq!(replay.start().food(), game.food());
+        }
+    }
+
+    mod finish {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let replay = new_replay();
+            let game = replay.finish();
+
+            assert_eq!(game.head(), 1);
+            assert!(game.finished());
+            assert_eq!(game.food(), replay.finish().food());
+        }
+    }
+
+    mod load {
+        use super::*;
+        use crate::snake::hunger::{Hunger, HungerRefill};
+
+        fn path(name: &str) -> std::path::PathBuf {
+            std::env::temp_dir().join(format!("snake-replay-{}-{}", std::process::id(), name))
+        }
+
+        #[test]
+        fn test() {
+            let replay = new_replay();
+            let path = path("round-trip");
+
+            replay.save(&path).unwrap();
+            let loaded = Replay::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded, replay);
+        }
+
+        #[test]
+        fn test_errors() {
+            let path = path("errors");
+
+            let mut replay = new_replay();
+            replay.config.food_count = 0;
+            replay.save(&path).unwrap();
+            assert!(matches!(Replay::load(&path), Err(SaveError::Invalid(_))));
+
+            // Hunger refuses a zero limit, so write one in by hand
+            let mut replay = new_replay();
+            replay.config.hunger = Some(Hunger::new(1, HungerRefill::Reset));
+            let json = serde_json::to_string(&replay)
+                .unwrap()
+                .replace("\"limit\":1", "\"limit\":0");
+            std::fs::write(&path, json).unwrap();
+            assert!(matches!(Replay::load(&path), Err(SaveError::Invalid(_))));
+
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
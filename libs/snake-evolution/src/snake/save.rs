@@ -0,0 +1,48 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Why a saved file could not be read or written.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// The file parsed, but its contents do not fit together.
+    Invalid(String),
+}
+
+// Reads and parses the JSON file at `path`
+pub(crate) fn load<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, SaveError> {
+    let json = std::fs::read_to_string(path).map_err(SaveError::Io)?;
+
+    serde_json::from_str(&json).map_err(SaveError::Json)
+}
+
+// Writes `value` as JSON to `path`, replacing anything already there
+pub(crate) fn save<T: Serialize>(value: &T, path: impl AsRef<Path>) -> Result<(), SaveError> {
+    let json = serde_json::to_string(value).map_err(SaveError::Json)?;
+
+    std::fs::write(path, json).map_err(SaveError::Io)
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "could not access file: {}", err),
+            SaveError::Json(err) => write!(f, "malformed file: {}", err),
+            SaveError::Invalid(reason) => write!(f, "invalid file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(err) => Some(err),
+            SaveError::Json(err) => Some(err),
+            SaveError::Invalid(_) => None,
+        }
+    }
+}
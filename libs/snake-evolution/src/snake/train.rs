@@ -0,0 +1,592 @@
+use std::collections::BTreeSet;
+
+use lib_genetic_algorithm::{
+    Chromosome, CrossoverMethod, GaussianMutation, GeneticAlgorithm, Individual,
+    RouletteWheelSelection, SelectionMethod, SinglePointCrossover, TournamentSelection,
+    UniformCrossover,
+};
+use lib_neural_network::{Activation, LayerTopology};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::snake::agent::{Evaluator, SnakeAgent};
+use crate::snake::checkpoint::Checkpoint;
+use crate::snake::config::GameConfig;
+use crate::snake::direction::{ActionSpace, Direction};
+use crate::snake::fitness::{Classic, FitnessFunction, ScoreAndSurvival, ScoreOnly};
+use crate::snake::food::{FoodRules, FoodSpawn};
+use crate::snake::game::{Boundary, ReversalPolicy};
+use crate::snake::hunger::{self, Hunger, HungerRefill};
+use crate::snake::observation::{Encoding, RayVision};
+
+/// How parents are picked from the population.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Selection {
+    /// With probability proportional to their fitness.
+    RouletteWheel,
+    /// As the fittest of this many individuals picked at random.
+    Tournament(usize),
+}
+
+impl SelectionMethod for Selection {
+    fn select<'a, I>(&self, rng: &mut dyn rand::RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        match *self {
+            Selection::RouletteWheel => RouletteWheelSelection::new().select(rng, population),
+            Selection::Tournament(size) => TournamentSelection::new(size).select(rng, population),
+        }
+    }
+}
+
+/// How the genes of two parents are combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Crossover {
+    /// Every gene comes from either parent at random.
+    Uniform,
+    /// Genes up to a random point come from one parent, the rest from the
+    /// other.
+    SinglePoint,
+}
+
+impl CrossoverMethod for Crossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn rand::RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        match self {
+            Crossover::Uniform => UniformCrossover::new().crossover(rng, parent_a, parent_b),
+            Crossover::SinglePoint => {
+                SinglePointCrossover::new().crossover(rng, parent_a, parent_b)
+            }
+        }
+    }
+}
+
+/// How finished games are scored, see [`fitness`](crate::snake::fitness).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fitness {
+    /// The final score.
+    Score,
+    /// The final score plus this much for every move survived.
+    ScoreAndSurvival(f32),
+    /// The usual formula for evolved snakes.
+    Classic,
+}
+
+impl Fitness {
+    /// Fitness function that scores games this way.
+    pub fn function(&self) -> Box<dyn FitnessFunction> {
+        match *self {
+            Fitness::Score => Box::new(ScoreOnly),
+            Fitness::ScoreAndSurvival(survival) => Box::new(ScoreAndSurvival { survival }),
+            Fitness::Classic => Box::new(Classic),
+        }
+    }
+}
+
+/// Parameters of a training run.
+///
+/// When read from a file, parameters left out take their values from
+/// [`TrainConfig::default`]. That goes for each rule of the game too, so a
+/// game table that only changes the board size keeps the default hunger,
+/// observations and actions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrainConfig {
+    /// Number of agents in every generation.
+    pub population: usize,
+    /// Number of generations to evolve.
+    pub generations: usize,
+    /// Sizes of the hidden layers of the agents' networks. The input and
+    /// output layers follow from the game's observations and actions.
+    pub hidden: Vec<usize>,
+    /// Activation of the hidden layers.
+    pub activation: Activation,
+    /// Activation of the output layer.
+    pub output_activation: Activation,
+    /// Seed for everything random in the run.
+    pub seed: u64,
+    /// Games every agent plays per generation, on seeds shared by the whole
+    /// generation.
+    pub games: usize,
+    /// Moves after which a game is stopped.
+    pub max_steps: usize,
+    /// How parents are picked.
+    pub selection: Selection,
+    /// How parents' genes are combined.
+    pub crossover: Crossover,
+    /// Probability of mutating each gene of a child.
+    pub mutation_rate: f32,
+    /// Largest change a mutation makes to a gene.
+    pub mutation_strength: f32,
+    /// How finished games are scored.
+    pub fitness: Fitness,
+    /// Rules of the games played.
+    #[serde(deserialize_with = "default_game")]
+    pub game: GameConfig,
+}
+
+impl Default for TrainConfig {
+    /// A small run on a 10x10 board, with ray vision, relative actions and
+    /// snakes that starve after 100 moves without eating.
+    fn default() -> TrainConfig {
+        TrainConfig {
+            population: 100,
+            generations: 50,
+            hidden: vec![16],
+            activation: Activation::Relu,
+            output_activation: Activation::Linear,
+            seed: 0,
+            games: 3,
+            max_steps: 1000,
+            selection: Selection::RouletteWheel,
+            crossover: Crossover::Uniform,
+            mutation_rate: 0.05,
+            mutation_strength: 0.3,
+            fitness: Fitness::Classic,
+            game: GameConfig {
+                hunger: Some(Hunger::new(100, HungerRefill::Reset)),
+                observation: Encoding::Rays(RayVision::default()),
+                actions: ActionSpace::Relative,
+                ..GameConfig::new(10, 10)
+            },
+        }
+    }
+}
+
+// Reads the rules of the games played, taking the rules left out from
+// `TrainConfig::default` rather than the classic rules of `GameConfig`
+fn default_game<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameConfig, D::Error> {
+    let given = GameTable::deserialize(deserializer)?;
+
+    Ok(given.or(TrainConfig::default().game))
+}
+
+// A game table as written, with `None` for each rule left out
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GameTable {
+    width: Option<isize>,
+    height: Option<isize>,
+    boundary: Option<Boundary>,
+    walls: Option<BTreeSet<isize>>,
+    #[serde(default, deserialize_with = "given_hunger")]
+    hunger: Option<Option<Hunger>>,
+    start_length: Option<usize>,
+    #[serde(default, deserialize_with = "given")]
+    spawn: Option<Option<isize>>,
+    #[serde(default, deserialize_with = "given")]
+    heading: Option<Option<Direction>>,
+    growth: Option<usize>,
+    reversal: Option<ReversalPolicy>,
+    food_count: Option<usize>,
+    food_spawn: Option<FoodSpawn>,
+    food_rules: Option<FoodRules>,
+    observation: Option<Encoding>,
+    actions: Option<ActionSpace>,
+}
+
+impl GameTable {
+    // The given rules, with the rest taken from `defaults`
+    fn or(self, defaults: GameConfig) -> GameConfig {
+        GameConfig {
+            width: self.width.unwrap_or(defaults.width),
+            height: self.height.unwrap_or(defaults.height),
+            boundary: self.boundary.unwrap_or(defaults.boundary),
+            walls: self.walls.unwrap_or(defaults.walls),
+            hunger: self.hunger.unwrap_or(defaults.hunger),
+            start_length: self.start_length.unwrap_or(defaults.start_length),
+            spawn: self.spawn.unwrap_or(defaults.spawn),
+            heading: self.heading.unwrap_or(defaults.heading),
+            growth: self.growth.unwrap_or(defaults.growth),
+            reversal: self.reversal.unwrap_or(defaults.reversal),
+            food_count: self.food_count.unwrap_or(defaults.food_count),
+            food_spawn: self.food_spawn.unwrap_or(defaults.food_spawn),
+            food_rules: self.food_rules.unwrap_or(defaults.food_rules),
+            observation: self.observation.unwrap_or(defaults.observation),
+            actions: self.actions.unwrap_or(defaults.actions),
+        }
+    }
+}
+
+// Tells a rule given as null apart from one left out
+fn given<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    T::deserialize(deserializer).map(Some)
+}
+
+fn given_hunger<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<Hunger>>, D::Error> {
+    hunger::optional::deserialize(deserializer).map(Some)
+}
+
+impl TrainConfig {
+    /// Shape of the agents' networks.
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let inputs = self.game.observer().size();
+        let outputs = self.game.actions.size();
+
+        std::iter::once(inputs)
+            .chain(self.hidden.iter().cloned())
+            .chain(std::iter::once(outputs))
+            .map(|neurons| LayerTopology { neurons })
+            .collect()
+    }
+
+    /// Activation of every layer of the agents' networks after the first.
+    pub fn activations(&self) -> Vec<Activation> {
+        let mut activations = vec![self.activation; self.hidden.len()];
+        activations.push(self.output_activation);
+
+        activations
+    }
+
+    /// Checks that the parameters describe a run that can be started,
+    /// explaining the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.population == 0 {
+            return Err("population must be at least 1".to_string());
+        }
+        if self.games == 0 {
+            return Err("games must be at least 1".to_string());
+        }
+        if self.hidden.contains(&0) {
+            return Err("hidden layers must have at least 1 neuron".to_string());
+        }
+        if self.selection == Selection::Tournament(0) {
+            return Err("selection tournament size must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.mutation_rate) {
+            return Err("mutation_rate must be between 0 and 1".to_string());
+        }
+        if !self.mutation_strength.is_finite() || self.mutation_strength < 0.0 {
+            return Err("mutation_strength must be a non-negative number".to_string());
+        }
+        if let Fitness::ScoreAndSurvival(survival) = self.fitness {
+            if !survival.is_finite() {
+                return Err("fitness survival reward must be a number".to_string());
+            }
+        }
+
+        self.game.validate().map_err(|err| format!("game.{}", err))
+    }
+}
+
+/// Summary of how a generation did.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    /// Number of the generation, counting from 0.
+    pub generation: usize,
+    /// Highest fitness in the generation.
+    pub best: f32,
+    /// Mean fitness of the generation.
+    pub average: f32,
+    /// Lowest fitness in the generation.
+    pub worst: f32,
+}
+
+/// Evolves a population of snake agents one generation at a time.
+///
+/// Every agent in a generation plays the same seeded games, so runs started
+/// from the same config evolve identically, as do runs resumed from a
+/// [`Checkpoint`] of one.
+pub struct Trainer {
+    config: TrainConfig,
+    rng: ChaCha8Rng,
+    ga: GeneticAlgorithm<Selection>,
+    evaluator: Evaluator,
+    population: Vec<SnakeAgent>,
+    generation: usize,
+    best: Option<SnakeAgent>,
+    history: Vec<GenerationStats>,
+}
+
+impl Trainer {
+    /// Starts a run from a random population following `config`.
+    pub fn new(config: &TrainConfig) -> Trainer {
+        assert!(config.population > 0);
+        assert!(config.games > 0);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+        let topology = config.topology();
+        let population = (0..config.population)
+            .map(|_| SnakeAgent::random(&mut rng, &topology))
+            .collect();
+
+        Trainer::resume(Checkpoint {
+            config: config.clone(),
+            rng,
+            population,
+            generation: 0,
+            best: None,
+            history: Vec::new(),
+            output: None,
+        })
+    }
+
+    /// Picks a run up exactly where `checkpoint` left it.
+    pub fn resume(checkpoint: Checkpoint) -> Trainer {
+        let config = checkpoint.config;
+
+        Trainer {
+            ga: GeneticAlgorithm::new(
+                config.selection,
+                config.crossover,
+                GaussianMutation::new(config.mutation_rate, config.mutation_strength),
+            ),
+            evaluator: Evaluator {
+                config: config.game.clone(),
+                topology: config.topology(),
+                activations: config.activations(),
+                fitness: config.fitness.function(),
+                max_steps: config.max_steps,
+            },
+            config,
+            rng: checkpoint.rng,
+            population: checkpoint.population,
+            generation: checkpoint.generation,
+            best: checkpoint.best,
+            history: checkpoint.history,
+        }
+    }
+
+    /// Everything needed to resume the run from this point.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            config: self.config.clone(),
+            rng: self.rng.clone(),
+            population: self.population.clone(),
+            generation: self.generation,
+            best: self.best.clone(),
+            history: self.history.clone(),
+            output: None,
+        }
+    }
+
+    /// Evaluates the current generation, then breeds the next one from it.
+    pub fn evolve(&mut self) -> GenerationStats {
+        let seeds: Vec<u64> = (0..self.config.games).map(|_| self.rng.gen()).collect();
+        for agent in &mut self.population {
+            self.evaluator.evaluate(agent, &seeds);
+        }
+
+        let fitnesses = self.population.iter().map(|agent| agent.fitness());
+        let stats = GenerationStats {
+            generation: self.generation,
+            best: fitnesses.clone().fold(f32::MIN, f32::max),
+            average: fitnesses.clone().sum::<f32>() / self.population.len() as f32,
+            worst: fitnesses.fold(f32::MAX, f32::min),
+        };
+
+        // Ties go to the earliest agent
+        let fittest = self
+            .population
+            .iter()
+            .reduce(|best, agent| {
+                if agent.fitness() > best.fitness() {
+                    agent
+                } else {
+                    best
+                }
+            })
+            .unwrap();
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|best| fittest.fitness() > best.fitness())
+        {
+            self.best = Some(fittest.clone());
+        }
+
+        self.population = self.ga.step(&mut self.rng, &self.population);
+        self.generation += 1;
+        self.history.push(stats);
+
+        stats
+    }
+
+    /// Fittest agent of any generation evaluated so far.
+    pub fn best(&self) -> Option<&SnakeAgent> {
+        self.best.as_ref()
+    }
+
+    /// Agents of the generation that will be evaluated next.
+    pub fn population(&self) -> &[SnakeAgent] {
+        &self.population
+    }
+
+    /// Number of generations evolved so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Summaries of every generation evolved so far.
+    pub fn history(&self) -> &[GenerationStats] {
+        &self.history
+    }
+
+    /// Parameters of the run.
+    pub fn config(&self) -> &TrainConfig {
+        &self.config
+    }
+
+    /// Plays and scores agents for this run.
+    pub fn evaluator(&self) -> &Evaluator {
+        &self.evaluator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TrainConfig {
+        TrainConfig {
+            population: 10,
+            generations: 3,
+            hidden: vec![4],
+            games: 2,
+            max_steps: 100,
+            ..TrainConfig::default()
+        }
+    }
+
+    mod train_config {
+        use super::*;
+
+        #[test]
+        fn test_topology() {
+            let topology = TrainConfig {
+                hidden: vec![16, 8],
+                ..TrainConfig::default()
+            }
+            .topology();
+
+            let neurons: Vec<usize> = topology.iter().map(|layer| layer.neurons).collect();
+            assert_eq!(neurons, vec![RayVision::SIZE, 16, 8, 3]);
+        }
+
+        #[test]
+        fn test_activations() {
+            let config = TrainConfig {
+                hidden: vec![16, 8],
+                activation: Activation::Tanh,
+                output_activation: Activation::Sigmoid,
+                ..TrainConfig::default()
+            };
+
+            assert_eq!(
+                config.activations(),
+                vec![Activation::Tanh, Activation::Tanh, Activation::Sigmoid]
+            );
+        }
+
+        #[test]
+        fn test_validate() {
+            assert_eq!(TrainConfig::default().validate(), Ok(()));
+
+            let invalid = [
+                TrainConfig {
+                    population: 0,
+                    ..TrainConfig::default()
+                },
+                TrainConfig {
+                    hidden: vec![8, 0],
+                    ..TrainConfig::default()
+                },
+                TrainConfig {
+                    selection: Selection::Tournament(0),
+                    ..TrainConfig::default()
+                },
+                TrainConfig {
+                    mutation_rate: 1.5,
+                    ..TrainConfig::default()
+                },
+            ];
+            for config in invalid {
+                assert!(config.validate().is_err());
+            }
+
+            let mut config = TrainConfig::default();
+            config.game.food_count = 0;
+            assert_eq!(
+                config.validate(),
+                Err("game.food_count must be at least 1".to_string())
+            );
+        }
+    }
+
+    mod trainer {
+        use super::*;
+
+        #[test]
+        fn test() {
+            let mut trainer = Trainer::new(&config());
+            assert_eq!(trainer.population().len(), 10);
+            assert!(trainer.best().is_none());
+
+            for generation in 0..3 {
+                let stats = trainer.evolve();
+
+                assert_eq!(stats.generation, generation);
+                assert!(stats.worst <= stats.average && stats.average <= stats.best);
+                assert!(trainer.best().unwrap().fitness() >= stats.best);
+            }
+            assert_eq!(trainer.generation(), 3);
+            assert_eq!(trainer.history().len(), 3);
+            assert_eq!(trainer.population().len(), 10);
+        }
+
+        #[test]
+        fn test_deterministic() {
+            let mut a = Trainer::new(&config());
+            let mut b = Trainer::new(&config());
+
+            for _ in 0..2 {
+                assert_eq!(a.evolve(), b.evolve());
+            }
+            assert_eq!(a.population(), b.population());
+        }
+
+        #[test]
+        fn test_resume() {
+            let mut uninterrupted = Trainer::new(&config());
+            let mut interrupted = Trainer::new(&config());
+
+            uninterrupted.evolve();
+            interrupted.evolve();
+            let mut resumed = Trainer::resume(interrupted.checkpoint());
+            drop(interrupted);
+
+            for _ in 0..2 {
+                assert_eq!(resumed.evolve(), uninterrupted.evolve());
+            }
+            assert_eq!(resumed.population(), uninterrupted.population());
+            assert_eq!(resumed.best(), uninterrupted.best());
+            assert_eq!(resumed.history(), uninterrupted.history());
+        }
+
+        #[test]
+        fn test_choices() {
+            let mut trainer = Trainer::new(&TrainConfig {
+                selection: Selection::Tournament(3),
+                crossover: Crossover::SinglePoint,
+                fitness: Fitness::ScoreAndSurvival(0.1),
+                ..config()
+            });
+
+            let stats = trainer.evolve();
+            assert!(stats.best > 0.0);
+        }
+    }
+}